@@ -1,53 +1,67 @@
+#[cfg(feature = "gui")]
 use sfml::{
     graphics::{
         CircleShape, Color, RenderStates, RenderTarget, RenderWindow, Shape, Transformable,
     },
-    system::Vector2,
+    system::Vector2f,
 };
 
-use crate::math::{circumcenter_of_triangle, euclidian_distance};
+use crate::{
+    math::{circumcenter_of_triangle, euclidian_distance},
+    point::Point,
+};
+
+#[cfg(feature = "gui")]
+const MAX_CIRCLE_POINT_COUNT: usize = 500;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Circle {
-    center: Vector2<f64>,
+    center: Point,
     radius: f64,
 }
 
 impl Circle {
-    pub fn is_point_inside_circle(&self, point: Vector2<f64>) -> bool {
+    #[must_use]
+    pub const fn new(center: Point, radius: f64) -> Self {
+        Circle { center, radius }
+    }
+
+    #[must_use]
+    pub fn center(&self) -> Point {
+        self.center
+    }
+
+    #[must_use]
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    pub fn is_point_inside_circle(&self, point: Point) -> bool {
         let euclidian_distance = euclidian_distance(self.center, point);
         euclidian_distance < self.radius
     }
+}
 
+#[cfg(feature = "gui")]
+impl Circle {
     pub fn draw(&self, window: &mut RenderWindow, fill_color: Color, outline_color: Color) {
-        let mut circle = CircleShape::new(self.radius as f32, self.radius as usize);
+        // The super triangle's circumcircles are far larger than the window, so the point count
+        // can not simply follow the radius.
+        let point_count = (self.radius as usize).clamp(3, MAX_CIRCLE_POINT_COUNT);
+        let mut circle = CircleShape::new(self.radius as f32, point_count);
         circle.set_outline_thickness(1.);
-        circle.set_position(self.center.as_other());
-        circle.set_origin(Vector2::new(self.radius as f32, self.radius as f32));
+        circle.set_position(self.center);
+        circle.set_origin(Vector2f::new(self.radius as f32, self.radius as f32));
         circle.set_fill_color(fill_color);
         circle.set_outline_color(outline_color);
         window.draw_circle_shape(&circle, &RenderStates::default());
     }
 }
 
-impl From<[Vector2<f64>; 3]> for Circle {
-    fn from(triangle: [Vector2<f64>; 3]) -> Self {
-        let (side_a, side_b, side_c) = (
-            euclidian_distance(triangle[0], triangle[1]),
-            euclidian_distance(triangle[1], triangle[2]),
-            euclidian_distance(triangle[2], triangle[0]),
-        );
-
-        // r = (abc)/((a+b+c)(b+c-a)(c+a-b)(a+b-c))^.5
-        let numerator = side_a * side_b * side_c;
-        let denominator1 = side_a + side_b + side_c;
-        let denominator2 = side_b + side_c - side_a;
-        let denominator3 = side_c + side_a - side_b;
-        let denominator4 = side_a + side_b - side_c;
-        let denominator = (denominator1 * denominator2 * denominator3 * denominator4).sqrt();
-        let radius = numerator / denominator;
-
+impl From<[Point; 3]> for Circle {
+    fn from(triangle: [Point; 3]) -> Self {
         let center = circumcenter_of_triangle(triangle);
+        let radius = euclidian_distance(center, triangle[0]);
 
         Circle { center, radius }
     }
@@ -0,0 +1,163 @@
+#[cfg(feature = "gui")]
+use sfml::graphics::{Color, PrimitiveType, RenderStates, RenderTarget, RenderWindow, Vertex};
+
+use crate::{
+    delauney_triangulation::DelauneyTriangulationInformation, mesh::HalfEdge, point::Point,
+};
+
+impl DelauneyTriangulationInformation {
+    /// Contour lines of the surface [`Self::interpolate`] describes, for each of `levels` the
+    /// polylines along which the surface has that value, found by marching through the
+    /// triangles. Each polyline runs with higher values on its left. Closed loops repeat their
+    /// first point at the end, and the others start and end on the border of the mesh. A vertex
+    /// exactly at a level counts as above it. There are no contours when the points have no
+    /// values.
+    #[must_use]
+    pub fn contours(&self, levels: &[f64]) -> Vec<Vec<Vec<Point>>> {
+        levels
+            .iter()
+            .map(|level| self.contours_at_level(*level))
+            .collect()
+    }
+
+    fn contours_at_level(&self, level: f64) -> Vec<Vec<Point>> {
+        let mesh = self.mesh();
+        let is_above = |vertex_idx: usize| self.values()[vertex_idx] >= level;
+        let crosses = |half_edge: HalfEdge, from_above: bool| {
+            let (origin, destination) = mesh.edge_vertices(half_edge);
+            is_above(origin) == from_above && is_above(destination) != from_above
+        };
+        // A triangle the level cuts through has one edge going down across it, where its
+        // piece of contour starts, and one going back up, where the piece ends.
+        let piece_starts: Vec<Option<HalfEdge>> = mesh
+            .triangles()
+            .iter()
+            .enumerate()
+            .map(|(triangle_idx, triangle)| {
+                if triangle
+                    .iter()
+                    .any(|vertex_idx| *vertex_idx >= self.values().len())
+                {
+                    return None;
+                }
+                (0..3)
+                    .map(|edge| HalfEdge::new(triangle_idx, edge))
+                    .find(|half_edge| crosses(*half_edge, true))
+            })
+            .collect();
+        let piece_end = |triangle_idx: usize| {
+            (0..3)
+                .map(|edge| HalfEdge::new(triangle_idx, edge))
+                .find(|half_edge| crosses(*half_edge, false))
+                .expect("a triangle going down across a level goes back up across it")
+        };
+
+        // The piece across an edge, in the triangle on its other side.
+        let piece_across = |half_edge: HalfEdge| {
+            mesh.twin(half_edge)
+                .map(|twin| twin.triangle)
+                .filter(|triangle_idx| piece_starts[*triangle_idx].is_some())
+        };
+
+        // Pieces starting on the border begin open polylines, and whatever is left over after
+        // following those forms closed loops.
+        let open_starts: Vec<usize> = (0..mesh.len())
+            .filter(|triangle_idx| {
+                piece_starts[*triangle_idx].is_some_and(|start| piece_across(start).is_none())
+            })
+            .collect();
+        let mut is_joined = vec![false; mesh.len()];
+        let mut polylines = vec![];
+        for first_triangle in open_starts.into_iter().chain(0..mesh.len()) {
+            let Some(start) = piece_starts[first_triangle] else {
+                continue;
+            };
+            if is_joined[first_triangle] {
+                continue;
+            }
+
+            let mut polyline = vec![self.contour_crossing(start, level)];
+            let mut triangle_idx = first_triangle;
+            loop {
+                is_joined[triangle_idx] = true;
+                let end = piece_end(triangle_idx);
+                polyline.push(self.contour_crossing(end, level));
+                match piece_across(end) {
+                    Some(next_triangle) if !is_joined[next_triangle] => {
+                        triangle_idx = next_triangle;
+                    }
+                    _ => break,
+                }
+            }
+            polylines.push(polyline);
+        }
+
+        polylines
+    }
+
+    /// Where the surface along an edge has the value `level`. Both half-edges of an edge give
+    /// exactly the same point, so the pieces of a contour line up.
+    fn contour_crossing(&self, half_edge: HalfEdge, level: f64) -> Point {
+        let (origin, destination) = self.mesh().edge_vertices(half_edge);
+        let (a, b) = (origin.min(destination), origin.max(destination));
+        let (a_value, b_value) = (self.values()[a], self.values()[b]);
+        let along = (level - a_value) / (b_value - a_value);
+
+        self.vertex(a) + (self.vertex(b) - self.vertex(a)) * along
+    }
+}
+
+#[cfg(feature = "gui")]
+impl DelauneyTriangulationInformation {
+    /// Draws the contour lines at `levels`, graded from blue for the first level to red for the
+    /// last.
+    pub fn draw_contours(&self, window: &mut RenderWindow, levels: &[f64]) {
+        let last_level = levels.len().saturating_sub(1).max(1) as f64;
+        let contour_lines: Vec<Vertex> = self
+            .contours(levels)
+            .iter()
+            .enumerate()
+            .flat_map(|(level_idx, polylines)| {
+                let grade = level_idx as f64 / last_level;
+                let color = Color::rgb((255. * grade) as u8, 64, (255. * (1. - grade)) as u8);
+                polylines.iter().flat_map(move |polyline| {
+                    polyline
+                        .windows(2)
+                        .flat_map(move |piece| piece.iter().map(move |point| (*point, color)))
+                })
+            })
+            .map(|(point, color)| Vertex::with_pos_color(point.into(), color))
+            .collect();
+        window.draw_primitives(&contour_lines, PrimitiveType::LINES, &RenderStates::DEFAULT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contours_of_a_linear_surface_are_straight() {
+        let points: Vec<Point> = (0..11)
+            .flat_map(|x| (0..11).map(move |y| Point::new(x as f64 * 10., y as f64 * 10.)))
+            .collect();
+        // The surface rises to the right, so the contours run straight down.
+        let values = points.iter().map(|point| point.x).collect();
+        let mut triangulation = DelauneyTriangulationInformation::default();
+        triangulation.set_point_list(points, Some(values)).unwrap();
+        triangulation.finish_triangulation();
+
+        let contours = triangulation.contours(&[25.5, 60.25, -1., 150.]);
+        for (level, polylines) in [25.5, 60.25].into_iter().zip(&contours) {
+            let [polyline] = polylines.as_slice() else {
+                panic!("{} polylines at {level}", polylines.len());
+            };
+            assert!(polyline.iter().all(|point| (point.x - level).abs() < 1e-9));
+            assert!(polyline.windows(2).all(|pair| pair[1].y < pair[0].y));
+            assert_eq!(polyline.first().unwrap().y, 100.);
+            assert_eq!(polyline.last().unwrap().y, 0.);
+        }
+        assert!(contours[2].is_empty());
+        assert!(contours[3].is_empty());
+    }
+}
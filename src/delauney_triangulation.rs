@@ -1,17 +1,37 @@
+#[cfg(feature = "gui")]
 use sfml::{
     graphics::{
         CircleShape, Color, PrimitiveType, RenderStates, RenderTarget, RenderWindow, Shape,
         Transformable, Vertex,
     },
-    system::Vector2,
+    system::Vector2f,
 };
 
+#[cfg(feature = "gui")]
+use crate::utils::{self, display_triangles, triangle_to_lines};
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
 use crate::{
     circle::Circle,
-    math::{edges_are_equal, get_edges_from_triangle},
-    utils::{self, display_triangles},
+    error::TriangulationError,
+    insertion_order::{sort_point_indices, PointOrdering},
+    math::{
+        barycentric_coordinates, bounding_box, calculate_angles_of_triangle,
+        circumcenter_of_triangle, euclidian_distance, get_edges_from_triangle,
+        is_point_inside_circumcircle, orient2d, polygon_area,
+    },
+    mesh::{HalfEdge, IndexHashMap, IndexHashSet, TriangleMesh},
+    point::Point,
 };
 
+/// Indices the super triangle's vertices are referred to by in the mesh while the triangulation
+/// is running. They are chosen so they can never collide with an index into the point list.
+const SUPER_TRIANGLE_VERTICES: [usize; 3] = [usize::MAX - 2, usize::MAX - 1, usize::MAX];
+/// How many times the extent of the point list the super triangle reaches out from its center.
+const SUPER_TRIANGLE_SCALE: f64 = 1e4;
+/// Fraction of the extent of the point list below which refinement stops splitting edges.
+const REFINEMENT_EDGE_TOLERANCE: f64 = 1e-6;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 enum InternalState {
     #[default]
@@ -20,6 +40,7 @@ enum InternalState {
     PolygonalHole,
     RemoveBadTrianglesFromMesh(bool),
     AddTrianglesFromPolygonEdges,
+    Finished,
 }
 
 impl PartialOrd for InternalState {
@@ -29,7 +50,7 @@ impl PartialOrd for InternalState {
 }
 
 impl InternalState {
-    fn to_usize(&self) -> usize {
+    fn to_usize(self) -> usize {
         use InternalState::*;
         match self {
             Initial => 0,
@@ -37,21 +58,73 @@ impl InternalState {
             PolygonalHole => 2,
             RemoveBadTrianglesFromMesh(_) => 3,
             AddTrianglesFromPolygonEdges => 4,
+            Finished => 5,
         }
     }
 }
 
+/// Where a walk towards a point ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WalkEnd {
+    /// The triangle containing the point.
+    Triangle(usize),
+    /// The point lies outside the mesh, on the far side of this border half-edge.
+    OutsideMesh(HalfEdge),
+}
+
+/// What a segment between two vertices runs through in the mesh.
+#[derive(Debug, Clone, Default)]
+struct SegmentTrace {
+    /// The first vertex on the segment, which is its other end unless it passes through another
+    /// vertex on the way.
+    end: usize,
+    crossed_triangles: Vec<usize>,
+    /// Vertices of the crossed triangles left of the segment, in order along it.
+    left_vertices: Vec<usize>,
+    /// Vertices of the crossed triangles right of the segment, in order along it.
+    right_vertices: Vec<usize>,
+    /// Whether the segment runs into a constrained edge, where the trace stops.
+    crosses_constraint: bool,
+}
+
+/// Quality goal of a refinement in progress and the work left towards it.
+#[derive(Debug, Clone, Default)]
+struct Refinement {
+    /// Smallest angle a triangle may have, in radians.
+    minimum_angle: f64,
+    maximum_area: Option<f64>,
+    /// Edges shorter than this are left alone, which keeps the refinement from running forever
+    /// next to input angles too small for the goal.
+    shortest_edge: f64,
+    /// Segments, as half-edges by their vertices, that may have a vertex inside their diametral
+    /// circle.
+    encroached_segments: Vec<(usize, usize)>,
+    /// Triangles that may miss the goal.
+    bad_triangles: Vec<[usize; 3]>,
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct DelauneyTriangulationInformation {
     state: InternalState,
     current_point_idx: usize,
-    point_list: Vec<Vector2<f64>>,
-    triangulation_mesh: Vec<Vertex>,
-    bad_triangles_to_plot: Vec<[Vector2<f64>; 3]>,
-    good_checked_triangles_to_plot: Vec<[Vector2<f64>; 3]>,
-    super_triangle: Option<[Vector2<f64>; 3]>,
+    point_list: Vec<Point>,
+    /// Scalar value of each point, or empty when the points have none.
+    values: Vec<f64>,
+    /// Indices into `point_list` in the order they get inserted, leaving out duplicates.
+    insertion_order: Vec<usize>,
+    merged_points: Vec<(usize, usize)>,
+    /// The first point at each position, used to merge duplicates.
+    point_indices_by_position: HashMap<(u64, u64), usize>,
+    point_ordering: PointOrdering,
+    /// Pairs of point indices whose connecting segment has to be in the mesh.
+    constraints: Vec<(usize, usize)>,
+    triangulation_mesh: TriangleMesh,
+    bad_triangles_to_plot: Vec<[Point; 3]>,
+    good_checked_triangles_to_plot: Vec<[Point; 3]>,
+    super_triangle: Option<[Point; 3]>,
     circumcircles_to_plot: Vec<Circle>,
-    polygon_for_new_triangles: Vec<(Vector2<f64>, Vector2<f64>)>,
+    polygon_for_new_triangles: Vec<(usize, usize)>,
+    refinement: Option<Refinement>,
 }
 
 impl DelauneyTriangulationInformation {
@@ -59,12 +132,18 @@ impl DelauneyTriangulationInformation {
         self.state = InternalState::Initial;
         self.current_point_idx = 0;
         self.point_list = Default::default();
+        self.values = Default::default();
+        self.insertion_order = Default::default();
+        self.merged_points = Default::default();
+        self.point_indices_by_position = Default::default();
+        self.constraints = Default::default();
         self.triangulation_mesh = Default::default();
         self.bad_triangles_to_plot = Default::default();
         self.good_checked_triangles_to_plot = Default::default();
         self.super_triangle = Default::default();
         self.circumcircles_to_plot = Default::default();
         self.polygon_for_new_triangles = Default::default();
+        self.refinement = Default::default();
     }
 
     fn clear_crap_to_plot(&mut self) {
@@ -73,16 +152,174 @@ impl DelauneyTriangulationInformation {
         self.good_checked_triangles_to_plot = Default::default();
     }
 
-    pub fn set_point_list(&mut self, point_list: Vec<Vector2<f64>>) {
+    /// Replaces the points to triangulate, optionally with a scalar value for each of them, like
+    /// the elevations of a terrain. A point at the same position as an earlier one is not
+    /// inserted again, see [`Self::merged_points`]. The triangulation starts over, so the mesh,
+    /// the constraints and any refinement of the old points get dropped, and only the point
+    /// ordering is kept.
+    pub fn set_point_list(
+        &mut self,
+        point_list: Vec<Point>,
+        values: Option<Vec<f64>>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(point_idx) = point_list.iter().position(|point| !point.is_finite()) {
+            return Err(TriangulationError::NonFiniteCoordinate(point_idx));
+        }
+        let values = values.unwrap_or_default();
+        if !values.is_empty() && values.len() != point_list.len() {
+            return Err(TriangulationError::ValueCountMismatch(
+                values.len(),
+                point_list.len(),
+            ));
+        }
+
+        self.reset_delauney_mesh();
+        for (point_idx, point) in point_list.iter().enumerate() {
+            match self.point_indices_by_position.entry(point.to_bits()) {
+                Entry::Occupied(kept_point) => {
+                    self.merged_points.push((point_idx, *kept_point.get()));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(point_idx);
+                    self.insertion_order.push(point_idx);
+                }
+            }
+        }
+        sort_point_indices(&point_list, &mut self.insertion_order, self.point_ordering);
         self.point_list = point_list;
+        self.values = values;
+
+        Ok(())
+    }
+
+    /// Pairs of `(duplicate, kept)` point indices. The duplicate point is left out of the mesh,
+    /// which uses the kept point in its place.
+    #[must_use]
+    pub fn merged_points(&self) -> &[(usize, usize)] {
+        &self.merged_points
+    }
+
+    /// Which order [`Self::set_point_list`] puts the points in for insertion. Takes effect on
+    /// the next call to it.
+    pub fn set_point_ordering(&mut self, point_ordering: PointOrdering) {
+        self.point_ordering = point_ordering;
+    }
+
+    /// Indices into the point list in the order the points get inserted, which maps the
+    /// insertion steps back to the original points. Merged duplicates are left out.
+    #[must_use]
+    pub fn insertion_order(&self) -> &[usize] {
+        &self.insertion_order
+    }
+
+    /// Sorts the points at `point_indices` from left to right, and from bottom to top where
+    /// they line up vertically. When all points lie on one line, this is their order along it.
+    #[must_use]
+    pub fn points_sorted_along_line(
+        &self,
+        point_indices: impl IntoIterator<Item = usize>,
+    ) -> Vec<usize> {
+        let mut sorted_points: Vec<usize> = point_indices.into_iter().collect();
+        sorted_points.sort_by(|a, b| {
+            let (a, b) = (self.vertex(*a), self.vertex(*b));
+            a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+        });
+        sorted_points
+    }
+
+    /// Index into the point list of the point currently being inserted.
+    fn current_point(&self) -> Option<usize> {
+        self.insertion_order.get(self.current_point_idx).copied()
+    }
+
+    #[must_use]
+    pub fn point_list(&self) -> &[Point] {
+        &self.point_list
+    }
+
+    /// Scalar value of each point in the point list, or nothing when none were given to
+    /// [`Self::set_point_list`]. Points added later take the value interpolated at their
+    /// position, so adding them leaves the surface the values describe as it was.
+    #[must_use]
+    pub fn values(&self) -> &[f64] {
+        &self.values
     }
 
+    /// Value for a point added at `point`, see [`Self::values`]. Outside the mesh, where there
+    /// is nothing to interpolate, the nearest point's value is taken.
+    fn value_for_new_point(&self, point: Point) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        self.interpolate(point).or_else(|| {
+            self.nearest_vertex(point)
+                .map(|nearest| self.values[nearest])
+        })
+    }
+
+    /// Mesh of indices into the point list. Once [`Self::is_finished`] returns true this is the
+    /// Delauney triangulation of the point list, which has no triangles at all when every point
+    /// lies on one line.
+    #[must_use]
+    pub fn mesh(&self) -> &TriangleMesh {
+        &self.triangulation_mesh
+    }
+
+    /// Position of a mesh vertex, which is either a point from the point list or a corner of
+    /// the super triangle.
+    #[must_use]
+    pub fn vertex(&self, vertex_idx: usize) -> Point {
+        match SUPER_TRIANGLE_VERTICES
+            .iter()
+            .position(|super_vertex| *super_vertex == vertex_idx)
+        {
+            Some(corner) => self.super_triangle.unwrap_or_default()[corner],
+            None => self.point_list[vertex_idx],
+        }
+    }
+
+    #[must_use]
+    pub fn triangle_points(&self, triangle: [usize; 3]) -> [Point; 3] {
+        triangle.map(|vertex_idx| self.vertex(vertex_idx))
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.state == InternalState::Finished
+    }
+
+    fn add_triangle_to_mesh(&mut self, triangle: [usize; 3]) {
+        self.triangulation_mesh.add_triangle(triangle);
+    }
+
+    fn iter_triangles_in_mesh(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.triangulation_mesh.triangles().iter().copied()
+    }
+}
+
+#[cfg(feature = "gui")]
+impl DelauneyTriangulationInformation {
     pub fn draw(&self, window: &mut RenderWindow) {
         for circle in &self.circumcircles_to_plot {
             circle.draw(window, Color::rgba(255, 215, 0, 50), Color::TRANSPARENT);
         }
+        let mesh_lines: Vec<Vertex> = self
+            .iter_triangles_in_mesh()
+            .flat_map(|triangle| triangle_to_lines(self.triangle_points(triangle), Color::WHITE))
+            .collect();
+        window.draw_primitives(&mesh_lines, PrimitiveType::LINES, &RenderStates::DEFAULT);
+        let constrained_lines: Vec<Vertex> = self
+            .triangulation_mesh
+            .constrained_edges()
+            .flat_map(|(a, b)| {
+                [a, b].map(|vertex_idx| {
+                    Vertex::with_pos_color(self.vertex(vertex_idx).into(), Color::MAGENTA)
+                })
+            })
+            .collect();
         window.draw_primitives(
-            &self.triangulation_mesh,
+            &constrained_lines,
             PrimitiveType::LINES,
             &RenderStates::DEFAULT,
         );
@@ -91,115 +328,404 @@ impl DelauneyTriangulationInformation {
 
         utils::display_vertices(window, &self.point_list, Color::YELLOW);
 
-        if let Some(current_point) = self.point_list.get(self.current_point_idx) {
+        if let Some(current_point) = self
+            .current_point()
+            .map(|point_idx| &self.point_list[point_idx])
+        {
             let mut circle = CircleShape::new(5., 20);
-            circle.set_origin(Vector2::new(circle.radius(), circle.radius()));
-            circle.set_position(current_point.as_other());
+            circle.set_origin(Vector2f::new(circle.radius(), circle.radius()));
+            circle.set_position(*current_point);
             circle.set_fill_color(Color::CYAN);
 
             window.draw_circle_shape(&circle, &Default::default());
         }
     }
 
-    fn add_triangle_to_mesh(&mut self, triangle: [Vector2<f64>; 3]) {
-        self.triangulation_mesh
-            .push(Vertex::with_pos(triangle[0].as_other()));
-        self.triangulation_mesh
-            .push(Vertex::with_pos(triangle[1].as_other()));
-        self.triangulation_mesh
-            .push(Vertex::with_pos(triangle[1].as_other()));
-        self.triangulation_mesh
-            .push(Vertex::with_pos(triangle[2].as_other()));
-        self.triangulation_mesh
-            .push(Vertex::with_pos(triangle[2].as_other()));
-        self.triangulation_mesh
-            .push(Vertex::with_pos(triangle[0].as_other()));
-    }
-
-    fn iter_triangles_in_mesh(&self) -> impl Iterator<Item = [Vector2<f64>; 3]> + '_ {
-        self.triangulation_mesh.chunks(6).map(|chunk| {
-            [
-                chunk[0].position.as_other(),
-                chunk[1].position.as_other(),
-                chunk[3].position.as_other(),
-            ]
-        })
-    }
-
-    fn remove_triangle_from_mesh(&mut self, triangle: [Vector2<f64>; 3]) {
-        let mut removal_index = Default::default();
-        for (idx, mesh_triangle) in self.iter_triangles_in_mesh().enumerate() {
-            if mesh_triangle == triangle {
-                removal_index = Some(idx * 6);
-            }
+    /// Highlights the triangle containing `point` and the vertex nearest to it.
+    pub fn draw_location(&self, window: &mut RenderWindow, point: Point) {
+        if let Some((triangle_idx, _)) = self.locate(point) {
+            let triangle = self.triangle_points(self.triangulation_mesh.triangles()[triangle_idx]);
+            let highlight = triangle
+                .map(|corner| Vertex::with_pos_color(corner.into(), Color::rgba(0, 191, 255, 80)));
+            window.draw_primitives(&highlight, PrimitiveType::TRIANGLES, &RenderStates::DEFAULT);
         }
+        if let Some(nearest) = self.nearest_vertex(point) {
+            let mut circle = CircleShape::new(5., 20);
+            circle.set_origin(Vector2f::new(circle.radius(), circle.radius()));
+            circle.set_position(self.point_list[nearest]);
+            circle.set_fill_color(Color::rgb(0, 191, 255));
 
-        if let Some(removal_index) = removal_index {
-            self.triangulation_mesh
-                .drain(removal_index..(removal_index + 6));
+            window.draw_circle_shape(&circle, &Default::default());
         }
     }
 }
 
 /// Delauney algorithm calculations
 impl DelauneyTriangulationInformation {
+    /// Puts an equilateral triangle around the bounding box of the point list. It is made much
+    /// larger than the points' spread so its far away vertices do not cut triangles off the
+    /// convex hull of the points.
     fn add_super_triangle(&mut self) {
-        let mut max = Vector2::default();
-        for point in &self.point_list {
-            if point.x > max.x {
-                max.x = point.x;
-            }
-            if point.y > max.y {
-                max.y = point.y;
-            }
+        let (min, max) = bounding_box(&self.point_list).unwrap_or_default();
+        let center = (min + max) / 2.;
+        let mut extent = (max.x - min.x).max(max.y - min.y);
+        if extent == 0. {
+            extent = 1.;
         }
-        max.x *= 2.;
-        max.y *= 2.;
 
+        // Distance from the center to each corner of the super triangle. The inscribed circle of
+        // an equilateral triangle has half that radius, which still dwarfs the bounding box.
+        // Kept finite for points near the largest floats, which then get a mesh without it,
+        // see [`Self::retriangulate_without_super_triangle`].
+        let radius = (extent * SUPER_TRIANGLE_SCALE).min(f64::MAX / 4.);
+        let (half_width, bottom) = (radius * 3_f64.sqrt() / 2., radius / 2.);
+        // Counterclockwise, like every triangle the mesh gets from here on.
         let super_triangle = [
-            Vector2::new(-1., -1.),
-            Vector2::new(max.x + 3., -1.),
-            Vector2::new(-1., max.y + 3.),
+            Point::new(center.x, center.y + radius),
+            Point::new(center.x - half_width, center.y - bottom),
+            Point::new(center.x + half_width, center.y - bottom),
         ];
         self.super_triangle = Some(super_triangle);
-        self.add_triangle_to_mesh(super_triangle);
+        self.add_triangle_to_mesh(SUPER_TRIANGLE_VERTICES);
     }
 
     fn remove_triangles_attached_to_super_triangle(&mut self) {
-        let Some(super_triangle) = self.super_triangle else {
+        if self.super_triangle.is_none() {
             return;
-        };
+        }
 
         let mut triangles_to_remove = vec![];
-        'triangle_loop: for triangle in self.iter_triangles_in_mesh() {
-            for super_vertex in super_triangle {
+        'triangle_loop: for (triangle_idx, triangle) in self.iter_triangles_in_mesh().enumerate() {
+            for super_vertex in SUPER_TRIANGLE_VERTICES {
                 for triangle_vertex in triangle {
                     if super_vertex == triangle_vertex {
-                        triangles_to_remove.push(triangle);
+                        triangles_to_remove.push(triangle_idx);
                         continue 'triangle_loop;
                     }
                 }
             }
         }
 
-        for triangle_to_remove in triangles_to_remove {
-            self.remove_triangle_from_mesh(triangle_to_remove);
+        self.triangulation_mesh
+            .remove_triangles(&triangles_to_remove);
+        if self.has_every_inserted_point() {
+            self.fill_border_dents();
+        } else {
+            self.retriangulate_without_super_triangle();
+        }
+    }
+
+    /// Whether every point inserted so far is a vertex of the mesh.
+    fn has_every_inserted_point(&self) -> bool {
+        let mut is_in_mesh = vec![false; self.point_list.len()];
+        for vertex_idx in self.iter_triangles_in_mesh().flatten() {
+            is_in_mesh[vertex_idx] = true;
+        }
+
+        self.insertion_order[..self.current_point_idx]
+            .iter()
+            .all(|point_idx| is_in_mesh[*point_idx])
+    }
+
+    /// Triangulates the inserted points again without the super triangle, for when removing it
+    /// left some of them out of the mesh. With nearly collinear points all triangles can be so
+    /// flat that their circumcircles reach past a corner of the super triangle, so none of them
+    /// get created. The mesh starts from the first three points that are not collinear, and the
+    /// others get added from outside of it like [`Self::insert_point`] does. Points that all lie
+    /// on one line leave the mesh empty.
+    fn retriangulate_without_super_triangle(&mut self) {
+        self.triangulation_mesh = Default::default();
+        let inserted_points = self.insertion_order[..self.current_point_idx].to_vec();
+        let [first, second] = match inserted_points[..] {
+            [first, second, ..] => [first, second],
+            _ => return,
+        };
+        let Some(third) = inserted_points[2..].iter().copied().find(|point_idx| {
+            orient2d(
+                self.vertex(first),
+                self.vertex(second),
+                self.vertex(*point_idx),
+            ) != 0.
+        }) else {
+            return;
+        };
+
+        if orient2d(self.vertex(first), self.vertex(second), self.vertex(third)) > 0. {
+            self.add_triangle_to_mesh([first, second, third]);
+        } else {
+            self.add_triangle_to_mesh([first, third, second]);
+        }
+        for point_idx in inserted_points {
+            if [first, second, third].contains(&point_idx) {
+                continue;
+            }
+            let walk_end = self.locate_triangle(self.point_list[point_idx]);
+            self.insert_point_into_mesh(point_idx, walk_end);
+        }
+    }
+
+    /// Makes the border of the mesh convex again after the super triangle is gone. Triangles
+    /// along the convex hull that are so flat their circumcircle reaches past a corner of the
+    /// super triangle never get created, which leaves dents in the border. Each dent gets
+    /// filled with a triangle, and the new triangles get flipped into Delauney ones.
+    ///
+    /// A border made of more than one loop means the mesh fell apart into pieces that only the
+    /// super triangle held together, or that touch at a single vertex. Filling dents can not
+    /// join those, so the mesh gets built again without the super triangle instead.
+    fn fill_border_dents(&mut self) {
+        let border_loops = self.border_loops();
+        let [border_loop] = &border_loops[..] else {
+            if border_loops.len() > 1 {
+                self.retriangulate_without_super_triangle();
+            }
+            return;
+        };
+
+        let mut border_length = border_loop.len();
+        let mut border_edge = border_loop[0];
+        let mut edges_to_legalize = vec![];
+        let mut checks_since_last_fill = 0;
+        while checks_since_last_fill < border_length {
+            let next_border_edge = self.triangulation_mesh.next_border_edge(border_edge);
+            let (a, b) = self.triangulation_mesh.edge_vertices(border_edge);
+            let c = self.triangulation_mesh.destination(next_border_edge);
+            if orient2d(self.vertex(a), self.vertex(b), self.vertex(c)) >= 0. {
+                checks_since_last_fill += 1;
+                border_edge = next_border_edge;
+                continue;
+            }
+
+            let triangle_idx = self.triangulation_mesh.add_triangle([a, c, b]);
+            edges_to_legalize.extend([(a, b), (b, c)]);
+            border_length -= 1;
+            checks_since_last_fill = 0;
+            // The turn at `a` may have become a dent too.
+            border_edge = self
+                .triangulation_mesh
+                .previous_border_edge(HalfEdge::new(triangle_idx, 0));
+        }
+        self.legalize_edges(edges_to_legalize);
+    }
+
+    /// Border half-edges of the mesh, grouped into the loops they form, each in the order they
+    /// follow each other. An empty mesh has none.
+    fn border_loops(&self) -> Vec<Vec<HalfEdge>> {
+        let mesh = &self.triangulation_mesh;
+        let mut is_walked = IndexHashSet::default();
+        let mut border_loops = vec![];
+        for start in (0..mesh.len())
+            .flat_map(|triangle_idx| (0..3).map(move |edge| HalfEdge::new(triangle_idx, edge)))
+            .filter(|half_edge| mesh.twin(*half_edge).is_none())
+        {
+            if !is_walked.insert(start) {
+                continue;
+            }
+            let mut border_loop = vec![start];
+            let mut border_edge = mesh.next_border_edge(start);
+            while border_edge != start {
+                is_walked.insert(border_edge);
+                border_loop.push(border_edge);
+                border_edge = mesh.next_border_edge(border_edge);
+            }
+            border_loops.push(border_loop);
+        }
+
+        border_loops
+    }
+
+    fn is_bad_triangle(&self, triangle: [usize; 3], point: Point) -> bool {
+        is_point_inside_circumcircle(self.triangle_points(triangle), point)
+    }
+
+    /// Walks from triangle to triangle towards `point`, starting at the most recently added
+    /// triangle. Each step crosses an edge that has the point on its far side, which in a
+    /// Delauney triangulation always reaches the point or the border of the mesh. `None` for an
+    /// empty mesh.
+    fn locate_triangle(&self, point: Point) -> Option<WalkEnd> {
+        let mut triangle_idx = self.triangulation_mesh.len().checked_sub(1)?;
+
+        'walk: for _ in 0..=self.triangulation_mesh.len() {
+            for edge in 0..3 {
+                let half_edge = HalfEdge::new(triangle_idx, edge);
+                let (origin, destination) = self.triangulation_mesh.edge_vertices(half_edge);
+                if orient2d(self.vertex(origin), self.vertex(destination), point) < 0. {
+                    match self.triangulation_mesh.twin(half_edge) {
+                        Some(twin) => triangle_idx = twin.triangle,
+                        None => return Some(WalkEnd::OutsideMesh(half_edge)),
+                    }
+                    continue 'walk;
+                }
+            }
+
+            return Some(WalkEnd::Triangle(triangle_idx));
+        }
+
+        None
+    }
+
+    /// Whether `point` lies on the outer side of a border half-edge, or on the edge itself.
+    fn is_border_edge_visible(&self, half_edge: HalfEdge, point: Point) -> bool {
+        let (origin, destination) = self.triangulation_mesh.edge_vertices(half_edge);
+        let (origin, destination) = (self.vertex(origin), self.vertex(destination));
+        let orientation = orient2d(origin, destination, point);
+        if orientation != 0. {
+            return orientation < 0.;
+        }
+
+        // The point is exactly on the edge's line, so comparing one coordinate is enough to
+        // tell whether it is between the edge's ends.
+        let is_strictly_between = |a: f64, b: f64, value: f64| a.min(b) < value && value < a.max(b);
+        if origin.x != destination.x {
+            is_strictly_between(origin.x, destination.x, point.x)
+        } else {
+            is_strictly_between(origin.y, destination.y, point.y)
+        }
+    }
+
+    /// Border half-edges of the mesh that `point` sees, found by following the border both ways
+    /// from `first_visible`.
+    fn border_edges_visible_from(&self, point: Point, first_visible: HalfEdge) -> Vec<HalfEdge> {
+        let mesh = &self.triangulation_mesh;
+        let mut visible_edges = vec![first_visible];
+
+        let mut next = mesh.next_border_edge(first_visible);
+        while next != first_visible && self.is_border_edge_visible(next, point) {
+            visible_edges.push(next);
+            next = mesh.next_border_edge(next);
+        }
+        if next == first_visible {
+            return visible_edges;
+        }
+
+        let mut previous = mesh.previous_border_edge(first_visible);
+        while self.is_border_edge_visible(previous, point) {
+            visible_edges.push(previous);
+            previous = mesh.previous_border_edge(previous);
+        }
+
+        visible_edges
+    }
+
+    /// Triangles whose circumcircle gets checked against `point`, in the order they are checked,
+    /// together with whether they are bad, and the border edges of the mesh the point sees. The
+    /// bad triangles always form one connected region around the point, so the search starts at
+    /// the triangle containing the point and only spreads across the edges of bad triangles.
+    /// Constrained edges block the search, which keeps them in the mesh.
+    /// For a point outside the mesh, the region is connected through the border edges it sees,
+    /// so the search starts at all of those instead. `walk_end` is where the point was located.
+    fn search_bad_triangles(
+        &self,
+        point: Point,
+        walk_end: Option<WalkEnd>,
+    ) -> (Vec<(usize, bool)>, Vec<HalfEdge>) {
+        let walk_end = walk_end.map(|walk_end| match walk_end {
+            // A point exactly on a border edge is treated like one outside it, so the edge gets
+            // replaced instead of ending up as the base of a flat triangle.
+            WalkEnd::Triangle(triangle_idx) => (0..3)
+                .map(|edge| HalfEdge::new(triangle_idx, edge))
+                .find(|half_edge| {
+                    self.triangulation_mesh.twin(*half_edge).is_none()
+                        && self.is_border_edge_visible(*half_edge, point)
+                })
+                .map_or(walk_end, WalkEnd::OutsideMesh),
+            WalkEnd::OutsideMesh(_) => walk_end,
+        });
+        let (start_triangles, visible_border_edges) = match walk_end {
+            Some(WalkEnd::Triangle(triangle_idx)) => (vec![triangle_idx], vec![]),
+            Some(WalkEnd::OutsideMesh(half_edge)) => {
+                let visible_border_edges = self.border_edges_visible_from(point, half_edge);
+                let start_triangles = visible_border_edges
+                    .iter()
+                    .map(|half_edge| half_edge.triangle)
+                    .collect();
+                (start_triangles, visible_border_edges)
+            }
+            None => (vec![], vec![]),
+        };
+        // The point cannot see past a constrained edge, so triangles behind one stay.
+        let hidden_triangles: IndexHashSet<usize> = visible_border_edges
+            .iter()
+            .filter(|half_edge| self.is_constrained_half_edge(**half_edge))
+            .map(|half_edge| half_edge.triangle)
+            .collect();
+        let check_triangle = |triangle_idx: usize| {
+            let is_bad = !hidden_triangles.contains(&triangle_idx)
+                && self.is_bad_triangle(self.triangulation_mesh.triangles()[triangle_idx], point);
+            (triangle_idx, is_bad)
+        };
+
+        let mut checked_triangles = vec![];
+        let mut visited = IndexHashSet::default();
+        for start_triangle in start_triangles {
+            if visited.insert(start_triangle) {
+                checked_triangles.push(check_triangle(start_triangle));
+            }
+        }
+        let mut next_to_spread_from = 0;
+        while let Some((triangle_idx, is_bad)) = checked_triangles.get(next_to_spread_from).copied()
+        {
+            next_to_spread_from += 1;
+            if !is_bad {
+                continue;
+            }
+
+            for (edge, neighbour) in self
+                .triangulation_mesh
+                .triangle_neighbours(triangle_idx)
+                .into_iter()
+                .enumerate()
+            {
+                let Some(neighbour) = neighbour else {
+                    continue;
+                };
+                if self.is_constrained_half_edge(HalfEdge::new(triangle_idx, edge)) {
+                    continue;
+                }
+                if visited.insert(neighbour) {
+                    checked_triangles.push(check_triangle(neighbour));
+                }
+            }
         }
+
+        (checked_triangles, visible_border_edges)
+    }
+
+    /// Bad triangles for `point` and the border edges of the mesh it sees.
+    fn get_all_bad_triangles_in_mesh(
+        &self,
+        point: Point,
+        walk_end: Option<WalkEnd>,
+    ) -> (Vec<usize>, Vec<HalfEdge>) {
+        let (checked_triangles, visible_border_edges) = self.search_bad_triangles(point, walk_end);
+        let bad_triangles = checked_triangles
+            .into_iter()
+            .filter(|(_, is_bad)| *is_bad)
+            .map(|(triangle_idx, _)| triangle_idx)
+            .collect();
+
+        (bad_triangles, visible_border_edges)
     }
 
     fn get_all_bad_triangles_in_mesh_and_circumcircles_checked(
         &mut self,
-        point: Vector2<f64>,
-    ) -> Vec<[Vector2<f64>; 3]> {
+        point: Point,
+    ) -> Vec<usize> {
         let mut bad_triangles = vec![];
         let mut crap_to_plot = None;
         let mut is_last_triangle_a_bad_triangle = false;
 
-        for (idx, triangle) in self.iter_triangles_in_mesh().enumerate() {
-            let circumcircle = Circle::from(triangle);
-            is_last_triangle_a_bad_triangle = circumcircle.is_point_inside_circle(point);
+        let walk_end = self.locate_triangle(point);
+        for (idx, (triangle_idx, is_bad_triangle)) in self
+            .search_bad_triangles(point, walk_end)
+            .0
+            .into_iter()
+            .enumerate()
+        {
+            let triangle = self.triangulation_mesh.triangles()[triangle_idx];
+            let circumcircle = Circle::from(self.triangle_points(triangle));
+            is_last_triangle_a_bad_triangle = is_bad_triangle;
             if is_last_triangle_a_bad_triangle {
-                bad_triangles.push(triangle);
+                bad_triangles.push(triangle_idx);
             }
 
             if let InternalState::GetBadTrianglesInMesh(current_idx) = self.state {
@@ -211,6 +737,7 @@ impl DelauneyTriangulationInformation {
         }
 
         if let Some((triangle, circumcircle)) = crap_to_plot {
+            let triangle = self.triangle_points(triangle);
             self.circumcircles_to_plot.push(circumcircle);
             if is_last_triangle_a_bad_triangle {
                 self.bad_triangles_to_plot.push(triangle);
@@ -230,45 +757,47 @@ impl DelauneyTriangulationInformation {
         bad_triangles
     }
 
+    /// Edges of the bad triangles that are not shared with another bad triangle, found by
+    /// looking at the triangle across each edge.
+    ///
+    /// A point outside the mesh also replaces the border edges it sees, as if there was a
+    /// triangle beyond each of them that it invalidates. Those edges drop out of the boundary
+    /// when the triangle inside is bad too, and otherwise become part of it.
     fn polygonal_hole_boundary(
-        bad_triangles: &[[Vector2<f64>; 3]],
-    ) -> Vec<(Vector2<f64>, Vector2<f64>)> {
+        &self,
+        bad_triangles: &[usize],
+        visible_border_edges: &[HalfEdge],
+    ) -> Vec<(usize, usize)> {
+        let bad_triangle_set: IndexHashSet<usize> = bad_triangles.iter().copied().collect();
         let mut polygon = vec![];
-        for (idx, triangle) in bad_triangles.iter().enumerate() {
-            let triangle_edges = get_edges_from_triangle(*triangle);
-            let mut triangle_edge_that_has_match = (false, false, false);
-
-            for (idx_c, triangle_c) in bad_triangles.iter().enumerate() {
-                if idx == idx_c {
-                    continue;
-                }
-
-                let triangle_edges_c = get_edges_from_triangle(*triangle_c);
+        for triangle_idx in bad_triangles {
+            for edge in 0..3 {
+                let half_edge = HalfEdge::new(*triangle_idx, edge);
+                let is_shared_with_bad_triangle = match self.triangulation_mesh.twin(half_edge) {
+                    Some(twin) => bad_triangle_set.contains(&twin.triangle),
+                    None => {
+                        visible_border_edges.contains(&half_edge)
+                            && !self.is_constrained_half_edge(half_edge)
+                    }
+                };
 
-                for edge in triangle_edges_c {
-                    triangle_edge_that_has_match.0 |= edges_are_equal(triangle_edges[0], edge);
-                    triangle_edge_that_has_match.1 |= edges_are_equal(triangle_edges[1], edge);
-                    triangle_edge_that_has_match.2 |= edges_are_equal(triangle_edges[2], edge);
+                if !is_shared_with_bad_triangle {
+                    polygon.push(self.triangulation_mesh.edge_vertices(half_edge));
                 }
             }
+        }
 
-            if !triangle_edge_that_has_match.0 {
-                polygon.push(triangle_edges[0]);
-            }
-
-            if !triangle_edge_that_has_match.1 {
-                polygon.push(triangle_edges[1]);
-            }
-
-            if !triangle_edge_that_has_match.2 {
-                polygon.push(triangle_edges[2]);
+        for half_edge in visible_border_edges {
+            if !bad_triangle_set.contains(&half_edge.triangle) {
+                let (origin, destination) = self.triangulation_mesh.edge_vertices(*half_edge);
+                polygon.push((destination, origin));
             }
         }
 
         polygon
     }
 
-    pub fn remove_all_bad_triangles_from_mesh(&mut self, bad_triangles: &[[Vector2<f64>; 3]]) {
+    pub fn remove_all_bad_triangles_from_mesh(&mut self, bad_triangles: &[usize]) {
         if let InternalState::RemoveBadTrianglesFromMesh(ran_through_removal_once) = self.state {
             if !ran_through_removal_once {
                 self.state = InternalState::RemoveBadTrianglesFromMesh(true);
@@ -277,80 +806,1756 @@ impl DelauneyTriangulationInformation {
                 return;
             }
         }
-        for triangle in bad_triangles {
-            self.remove_triangle_from_mesh(*triangle);
-        }
+        self.triangulation_mesh.remove_triangles(bad_triangles);
 
         self.state = InternalState::RemoveBadTrianglesFromMesh(true);
     }
 
     pub fn add_triangles_from_polygon_edges(
         &mut self,
-        polygon: &[(Vector2<f64>, Vector2<f64>)],
-        point: Vector2<f64>,
+        polygon: &[(usize, usize)],
+        point_idx: usize,
     ) {
         for edge in polygon {
-            let new_triangle = [point, edge.0, edge.1];
+            let new_triangle = [point_idx, edge.0, edge.1];
             self.add_triangle_to_mesh(new_triangle);
         }
     }
 
-    /// Early return indicates a draw up\date is needed.
-    ///
-    /// Psuedocode reference:
-    /// function BowyerWatson (pointList)
-    ///     // pointList is a set of coordinates defining the points to be triangulated
-    ///     triangulation := empty triangle mesh data structure
-    ///     add super-triangle to triangulation // must be large enough to completely contain all the points in pointList
-    ///     for each point in pointList do // add all the points one at a time to the triangulation
-    ///         badTriangles := empty set
-    ///         for each triangle in triangulation do // first find all the triangles that are no longer valid due to the insertion
-    ///             if point is inside circumcircle of triangle
-    ///                 add triangle to badTriangles
-    ///         polygon := empty set
-    ///         for each triangle in badTriangles do // find the boundary of the polygonal hole
-    ///             for each edge in triangle do
-    ///                 if edge is not shared by any other triangles in badTriangles
-    ///                     add edge to polygon
-    ///         for each triangle in badTriangles do // remove them from the data structure
-    ///             remove triangle from triangulation
-    ///         for each edge in polygon do // re-triangulate the polygonal hole
-    ///             newTri := form a triangle from edge to point
-    ///             add newTri to triangulation
-    ///     for each triangle in triangulation // done inserting points, now clean up
-    ///         if triangle contains a vertex from original super-triangle
-    ///             remove triangle from triangulation
-    ///     return triangulation    
-    pub fn update_triangulation(&mut self) {
-        self.clear_crap_to_plot();
+    /// Inserts a point in one go, without recording anything to plot. A point on a constrained
+    /// edge splits it in two. `walk_end` is where the point was located.
+    fn insert_point_into_mesh(&mut self, point_idx: usize, walk_end: Option<WalkEnd>) {
+        let point = self.point_list[point_idx];
+        let split_constraint = self.constrained_edge_through(point, walk_end);
+        if let Some((a, b)) = split_constraint {
+            self.triangulation_mesh.set_constrained(a, b, false);
+        }
 
-        if self.state == InternalState::Initial {
-            self.add_super_triangle();
-            self.state = InternalState::GetBadTrianglesInMesh(0);
-            return;
+        let (bad_triangles, visible_border_edges) =
+            self.get_all_bad_triangles_in_mesh(point, walk_end);
+        let polygon = self.polygonal_hole_boundary(&bad_triangles, &visible_border_edges);
+        self.triangulation_mesh.remove_triangles(&bad_triangles);
+        self.add_triangles_from_polygon_edges(&polygon, point_idx);
+
+        if let Some((a, b)) = split_constraint {
+            self.triangulation_mesh.set_constrained(a, point_idx, true);
+            self.triangulation_mesh.set_constrained(point_idx, b, true);
         }
+    }
 
-        let Some(point) = self.point_list.get(self.current_point_idx) else {
-            self.remove_triangles_attached_to_super_triangle();
-            self.bad_triangles_to_plot = Default::default();
-            self.circumcircles_to_plot = Default::default();
-            return;
+    fn is_constrained_half_edge(&self, half_edge: HalfEdge) -> bool {
+        let (origin, destination) = self.triangulation_mesh.edge_vertices(half_edge);
+        self.triangulation_mesh.is_constrained(origin, destination)
+    }
+
+    /// The constrained edge `point` lies on, if any, given where the point was located.
+    fn constrained_edge_through(
+        &self,
+        point: Point,
+        walk_end: Option<WalkEnd>,
+    ) -> Option<(usize, usize)> {
+        self.triangulation_mesh.constrained_edges().next()?;
+        let Some(WalkEnd::Triangle(triangle_idx)) = walk_end else {
+            return None;
         };
-        let point = *point; // added this line to deref `point` and make it no longer linked to point list
-        let bad_triangles = self.get_all_bad_triangles_in_mesh_and_circumcircles_checked(point);
-        if self.state < InternalState::PolygonalHole {
-            return;
+
+        (0..3)
+            .map(|edge| HalfEdge::new(triangle_idx, edge))
+            .filter(|half_edge| self.is_constrained_half_edge(*half_edge))
+            .map(|half_edge| self.triangulation_mesh.edge_vertices(half_edge))
+            .find(|(origin, destination)| {
+                orient2d(self.vertex(*origin), self.vertex(*destination), point) == 0.
+            })
+    }
+
+    /// Adds a point to the point list and to the triangulation, returning its index in the point
+    /// list. On a finished triangulation the point is inserted right away, wherever it lies.
+    /// Before the triangulation starts it is queued up behind the other points, and a
+    /// triangulation that is part way through gets finished first. A point at the position of an
+    /// existing one is merged into it like in [`Self::set_point_list`].
+    pub fn insert_point(&mut self, point: Point) -> Result<usize, TriangulationError> {
+        let point_idx = self.point_list.len();
+        if !point.is_finite() {
+            return Err(TriangulationError::NonFiniteCoordinate(point_idx));
         }
-        let polygon = Self::polygonal_hole_boundary(&bad_triangles);
-        self.remove_all_bad_triangles_from_mesh(&bad_triangles);
-        if self.state <= InternalState::RemoveBadTrianglesFromMesh(true) {
-            self.polygon_for_new_triangles = polygon;
+        if self.state != InternalState::Initial {
+            self.finish_triangulation();
+        }
+
+        if let Some(value) = self.value_for_new_point(point) {
+            self.values.push(value);
+        }
+        self.point_list.push(point);
+        self.attach_point(point_idx);
+
+        Ok(point_idx)
+    }
+
+    /// Merges the point at `point_idx` into an earlier point at the same position, or otherwise
+    /// gives it a place in the insertion order and, on a finished triangulation, in the mesh.
+    fn attach_point(&mut self, point_idx: usize) {
+        match self
+            .point_indices_by_position
+            .entry(self.point_list[point_idx].to_bits())
+        {
+            Entry::Occupied(kept_point) => {
+                self.merged_points.push((point_idx, *kept_point.get()));
+                return;
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(point_idx);
+            }
+        }
+        self.insertion_order.push(point_idx);
+        if self.state == InternalState::Initial {
             return;
         }
-        let polygon = self.polygon_for_new_triangles.clone();
-        self.add_triangles_from_polygon_edges(&polygon, point);
-        self.current_point_idx += 1;
 
-        self.state = InternalState::GetBadTrianglesInMesh(0);
+        if self.triangulation_mesh.is_empty() {
+            // Every point so far is on one line, so there is no mesh to insert into yet.
+            self.triangulation_mesh = Default::default();
+            self.super_triangle = Default::default();
+            self.current_point_idx = 0;
+            self.state = InternalState::Initial;
+            self.finish_triangulation();
+        } else {
+            let walk_end = self.locate_triangle(self.point_list[point_idx]);
+            self.insert_point_into_mesh(point_idx, walk_end);
+            self.current_point_idx += 1;
+        }
+    }
+
+    /// Removes the point at `point_idx` from the point list and from the triangulation, by moving
+    /// the last point into its slot, so only the index of the last point changes. The triangles
+    /// around the point are replaced by Delauney triangles filling the hole it leaves. Like
+    /// [`Self::insert_point`], this finishes a triangulation that is part way through first.
+    ///
+    /// When other points were merged into the removed one, the first of them takes its place in
+    /// the triangulation, so the position stays triangulated. Constraints ending at the point
+    /// are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `point_idx` is out of bounds.
+    pub fn remove_point(&mut self, point_idx: usize) -> Point {
+        assert!(
+            point_idx < self.point_list.len(),
+            "point index {point_idx} is out of bounds"
+        );
+        if self.state != InternalState::Initial {
+            self.finish_triangulation();
+        }
+
+        let mut unmarked_edges = self.unmark_constraints_at(point_idx);
+        self.constraints
+            .retain(|(a, b)| *a != point_idx && *b != point_idx);
+        self.detach_point(point_idx);
+        let removed_point = self.point_list.swap_remove(point_idx);
+        if !self.values.is_empty() {
+            self.values.swap_remove(point_idx);
+        }
+        let moved_from = self.point_list.len();
+        if point_idx < moved_from {
+            self.rename_point(moved_from, point_idx);
+            for vertex_idx in unmarked_edges.iter_mut().flat_map(|(a, b)| [a, b]) {
+                if *vertex_idx == moved_from {
+                    *vertex_idx = point_idx;
+                }
+            }
+        }
+        self.legalize_edges(unmarked_edges);
+        // The point may have split a constraint running through it.
+        self.recover_constraints();
+
+        removed_point
+    }
+
+    /// Moves the point at `point_idx` to `new_position`, keeping its index. The triangulation is
+    /// repaired by taking the point out of the mesh and inserting it again at its new position,
+    /// which only touches the triangles around the old and the new position. Like
+    /// [`Self::insert_point`], this finishes a triangulation that is part way through first, and
+    /// a point moved onto another one is merged into it. Constraints ending at the point move
+    /// along with it, also when other points were merged into it and stay behind. A constraint
+    /// that ends up crossing another one no longer fits into the mesh, and gets dropped from
+    /// [`Self::constraints`]. Returns the dropped constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `point_idx` is out of bounds.
+    pub fn move_point(
+        &mut self,
+        point_idx: usize,
+        new_position: Point,
+    ) -> Result<Vec<(usize, usize)>, TriangulationError> {
+        assert!(
+            point_idx < self.point_list.len(),
+            "point index {point_idx} is out of bounds"
+        );
+        if !new_position.is_finite() {
+            return Err(TriangulationError::NonFiniteCoordinate(point_idx));
+        }
+        if self.state != InternalState::Initial {
+            self.finish_triangulation();
+        }
+
+        let unmarked_edges = self.unmark_constraints_at(point_idx);
+        // Detaching hands the constraints over to a point merged into this one, if any.
+        let constraints_at_point: Vec<usize> = (0..self.constraints.len())
+            .filter(|constraint_idx| {
+                let (a, b) = self.constraints[*constraint_idx];
+                a == point_idx || b == point_idx
+            })
+            .collect();
+        let old_constraints = self.constraints.clone();
+        self.detach_point(point_idx);
+        self.legalize_edges(unmarked_edges);
+        self.point_list[point_idx] = new_position;
+        self.attach_point(point_idx);
+
+        // The point may have been merged into another one at its new position.
+        let kept_point = self.kept_point(point_idx);
+        for constraint_idx in constraints_at_point {
+            let (a, b) = old_constraints[constraint_idx];
+            let moved_end = |end: usize| if end == point_idx { kept_point } else { end };
+            self.constraints[constraint_idx] = (moved_end(a), moved_end(b));
+        }
+        self.constraints.retain(|(a, b)| a != b);
+
+        Ok(self.recover_constraints())
+    }
+
+    /// Undoes [`Self::attach_point`], leaving the point in the point list without anything
+    /// referring to it. Points merged into it get the first of them put in its place.
+    fn detach_point(&mut self, point_idx: usize) {
+        if let Some(merged_idx) = self
+            .merged_points
+            .iter()
+            .position(|(duplicate, _)| *duplicate == point_idx)
+        {
+            self.merged_points.remove(merged_idx);
+        } else if let Some(merged_idx) = self
+            .merged_points
+            .iter()
+            .position(|(_, kept)| *kept == point_idx)
+        {
+            let (replacement, _) = self.merged_points.remove(merged_idx);
+            self.rename_point(point_idx, replacement);
+        } else {
+            let order_idx = self
+                .insertion_order
+                .iter()
+                .position(|order_point_idx| *order_point_idx == point_idx)
+                .expect("every point that is not merged has a place in the insertion order");
+            self.insertion_order.remove(order_idx);
+            if order_idx < self.current_point_idx {
+                self.current_point_idx -= 1;
+                self.remove_point_from_mesh(point_idx);
+            }
+            self.point_indices_by_position
+                .remove(&self.point_list[point_idx].to_bits());
+        }
+    }
+
+    /// Repoints everything that refers to the point at `from` to `to`, which is expected to
+    /// already hold a point at the same position.
+    fn rename_point(&mut self, from: usize, to: usize) {
+        self.triangulation_mesh.rename_vertex(from, to);
+        for order_point_idx in &mut self.insertion_order {
+            if *order_point_idx == from {
+                *order_point_idx = to;
+            }
+        }
+        for point_idx in self
+            .merged_points
+            .iter_mut()
+            .chain(&mut self.constraints)
+            .flat_map(|(a, b)| [a, b])
+        {
+            if *point_idx == from {
+                *point_idx = to;
+            }
+        }
+        if let Some(kept) = self
+            .point_indices_by_position
+            .get_mut(&self.point_list[to].to_bits())
+            .filter(|kept| **kept == from)
+        {
+            *kept = to;
+        }
+    }
+
+    /// Takes a vertex out of the mesh and fills the hole left by the triangles around it.
+    fn remove_point_from_mesh(&mut self, point_idx: usize) {
+        let out_edges = self.triangulation_mesh.vertex_out_edges(point_idx);
+        if out_edges.is_empty() {
+            return;
+        }
+
+        // The edges opposite the vertex run counterclockwise around the hole.
+        let hole_edges: IndexHashMap<usize, usize> = out_edges
+            .iter()
+            .map(|half_edge| self.triangulation_mesh.edge_vertices(half_edge.next()))
+            .collect();
+        let start = hole_edges
+            .keys()
+            .copied()
+            .find(|origin| !hole_edges.values().any(|destination| destination == origin));
+        let is_closed = start.is_none();
+        let start = start.unwrap_or_else(|| self.triangulation_mesh.destination(out_edges[0]));
+
+        let mut hole = vec![start];
+        let mut current = start;
+        while let Some(next) = hole_edges.get(&current).copied() {
+            if next == start {
+                break;
+            }
+            hole.push(next);
+            current = next;
+        }
+
+        for vertex_idx in &hole {
+            self.triangulation_mesh
+                .set_constrained(point_idx, *vertex_idx, false);
+        }
+        let star: Vec<usize> = out_edges
+            .iter()
+            .map(|half_edge| half_edge.triangle)
+            .collect();
+        self.triangulation_mesh.remove_triangles(&star);
+        let new_triangles = self.fill_hole(hole, is_closed);
+        // Constrained edges can hide vertices from the triangles that were around the point,
+        // which the new triangles may be able to see, so every new edge gets checked again.
+        self.legalize_edges(
+            new_triangles
+                .iter()
+                .flat_map(|triangle| get_edges_from_triangle(*triangle))
+                .collect(),
+        );
+    }
+
+    /// Flips edges that are not locally Delauney until there are none left, starting from
+    /// `edges` and continuing with the edges around each flip. Constrained edges stay.
+    fn legalize_edges(&mut self, mut edges: Vec<(usize, usize)>) {
+        while let Some((a, b)) = edges.pop() {
+            let mesh = &self.triangulation_mesh;
+            if mesh.is_constrained(a, b) {
+                continue;
+            }
+            let Some(half_edge) = mesh.half_edge(a, b) else {
+                continue;
+            };
+            let Some(twin) = mesh.twin(half_edge) else {
+                continue;
+            };
+            let (left, right) = (mesh.origin(half_edge.prev()), mesh.origin(twin.prev()));
+            if !is_point_inside_circumcircle(self.triangle_points([a, b, left]), self.vertex(right))
+            {
+                continue;
+            }
+
+            self.triangulation_mesh
+                .remove_triangles(&[half_edge.triangle, twin.triangle]);
+            self.add_triangle_to_mesh([a, right, left]);
+            self.add_triangle_to_mesh([right, b, left]);
+            edges.extend([(a, right), (right, b), (b, left), (left, a)]);
+        }
+    }
+
+    /// Fills a hole in the mesh with Delauney triangles, by cutting off ears, preferring ones
+    /// whose circumcircle has none of the other hole vertices inside. `hole` lists the vertices
+    /// around the hole counterclockwise. A hole that is not closed reaches the border of the
+    /// mesh, and only gets filled until the border is convex again. Returns the triangles it
+    /// added.
+    fn fill_hole(&mut self, mut hole: Vec<usize>, is_closed: bool) -> Vec<[usize; 3]> {
+        let mut new_triangles = vec![];
+        while hole.len() >= 3 {
+            let ear_count = if is_closed {
+                hole.len()
+            } else {
+                hole.len() - 2
+            };
+            let ears: Vec<[usize; 3]> = (0..ear_count)
+                .map(|first| [first, (first + 1) % hole.len(), (first + 2) % hole.len()])
+                .filter(|ear| {
+                    let [a, b, c] = ear.map(|hole_idx| self.vertex(hole[hole_idx]));
+                    orient2d(a, b, c) > 0.
+                        && hole.iter().enumerate().all(|(hole_idx, vertex_idx)| {
+                            let vertex = self.vertex(*vertex_idx);
+                            ear.contains(&hole_idx)
+                                || orient2d(a, b, vertex) < 0.
+                                || orient2d(b, c, vertex) < 0.
+                                || orient2d(c, a, vertex) < 0.
+                        })
+                })
+                .collect();
+            let empty_ear = ears.iter().find(|ear| {
+                let triangle = ear.map(|hole_idx| self.vertex(hole[hole_idx]));
+                hole.iter().enumerate().all(|(hole_idx, vertex_idx)| {
+                    ear.contains(&hole_idx)
+                        || !is_point_inside_circumcircle(triangle, self.vertex(*vertex_idx))
+                })
+            });
+            // Around a point on a constrained edge there may be no ear with an empty
+            // circumcircle, in which case any ear will do and the flips afterwards fix it up.
+            let Some(ear) = empty_ear.or(ears.first()).copied() else {
+                break;
+            };
+
+            let new_triangle = ear.map(|hole_idx| hole[hole_idx]);
+            self.add_triangle_to_mesh(new_triangle);
+            new_triangles.push(new_triangle);
+            hole.remove(ear[1]);
+        }
+
+        new_triangles
+    }
+
+    /// Pairs of point indices whose connecting segment is forced into the mesh. Which mesh edges
+    /// ended up constrained is reported by [`TriangleMesh::constrained_edges`], where a
+    /// constraint passing through other points shows up in pieces.
+    #[must_use]
+    pub fn constraints(&self) -> &[(usize, usize)] {
+        &self.constraints
+    }
+
+    /// Replaces the constraints, which are segments between two points of the point list that
+    /// have to show up as mesh edges. They get put into the mesh once the triangulation is
+    /// finished, in the given order; a constraint crossing an earlier one is dropped.
+    pub fn set_constraints(
+        &mut self,
+        constraints: Vec<(usize, usize)>,
+    ) -> Result<(), TriangulationError> {
+        if let Some(point_idx) = constraints
+            .iter()
+            .flat_map(|(a, b)| [*a, *b])
+            .find(|point_idx| *point_idx >= self.point_list.len())
+        {
+            return Err(TriangulationError::PointIndexOutOfBounds(point_idx));
+        }
+
+        self.constraints = Default::default();
+        for (a, b) in constraints {
+            let (a, b) = (self.kept_point(a), self.kept_point(b));
+            if a != b {
+                self.constraints.push((a, b));
+            }
+        }
+        if self.state == InternalState::Finished {
+            let unmarked_edges = self.unmark_constrained_edges();
+            self.legalize_edges(unmarked_edges);
+            self.recover_constraints();
+        }
+
+        Ok(())
+    }
+
+    /// Adds a constraint between the points at `a` and `b`, see [`Self::set_constraints`]. On a
+    /// finished triangulation it is put into the mesh right away, and refused if it crosses
+    /// another constraint. Like [`Self::insert_point`], this finishes a triangulation that is
+    /// part way through first.
+    pub fn add_constraint(&mut self, a: usize, b: usize) -> Result<(), TriangulationError> {
+        if let Some(point_idx) = [a, b]
+            .into_iter()
+            .find(|point_idx| *point_idx >= self.point_list.len())
+        {
+            return Err(TriangulationError::PointIndexOutOfBounds(point_idx));
+        }
+        if self.state != InternalState::Initial {
+            self.finish_triangulation();
+        }
+
+        let (a, b) = (self.kept_point(a), self.kept_point(b));
+        if a == b {
+            return Ok(());
+        }
+        if self.state == InternalState::Finished {
+            self.recover_constraint(a, b)?;
+        }
+        self.constraints.push((a, b));
+
+        Ok(())
+    }
+
+    /// Clears the constrained edge marks of every constraint ending at `point_idx` and returns
+    /// the edges. The mesh has to be made Delauney around them again with
+    /// [`Self::legalize_edges`], after which the constraints that should stay can be recovered.
+    fn unmark_constraints_at(&mut self, point_idx: usize) -> Vec<(usize, usize)> {
+        let mut unmarked_edges = vec![];
+        for (a, b) in self.constraints.clone() {
+            if a != point_idx && b != point_idx {
+                continue;
+            }
+
+            // A constraint running through other points is marked in pieces along it.
+            let (a_point, b_point) = (self.vertex(a), self.vertex(b));
+            let distance_along = |point: Point| {
+                let (offset, direction) = (point - a_point, b_point - a_point);
+                offset.x * direction.x + offset.y * direction.y
+            };
+            let mut current = a;
+            while current != b {
+                let next_piece = self
+                    .triangulation_mesh
+                    .vertex_neighbours(current)
+                    .into_iter()
+                    .find(|next| {
+                        self.triangulation_mesh.is_constrained(current, *next)
+                            && orient2d(a_point, b_point, self.vertex(*next)) == 0.
+                            && distance_along(self.vertex(*next))
+                                > distance_along(self.vertex(current))
+                    });
+                let Some(next) = next_piece else {
+                    break;
+                };
+                self.triangulation_mesh
+                    .set_constrained(current, next, false);
+                unmarked_edges.push((current, next));
+                current = next;
+            }
+        }
+
+        unmarked_edges
+    }
+
+    /// Clears every constrained edge mark in the mesh and returns the edges, see
+    /// [`Self::unmark_constraints_at`].
+    fn unmark_constrained_edges(&mut self) -> Vec<(usize, usize)> {
+        let constrained_edges: Vec<(usize, usize)> =
+            self.triangulation_mesh.constrained_edges().collect();
+        for (a, b) in &constrained_edges {
+            self.triangulation_mesh.set_constrained(*a, *b, false);
+        }
+
+        constrained_edges
+    }
+
+    /// The point the mesh uses in place of the point at `point_idx`.
+    fn kept_point(&self, point_idx: usize) -> usize {
+        self.merged_points
+            .iter()
+            .find(|(duplicate, _)| *duplicate == point_idx)
+            .map_or(point_idx, |(_, kept)| *kept)
+    }
+
+    /// Puts every constraint into the mesh that is not in it yet. Constraints crossing an
+    /// earlier one are dropped from [`Self::constraints`] and returned.
+    fn recover_constraints(&mut self) -> Vec<(usize, usize)> {
+        let mut crossing_constraints = vec![];
+        for (a, b) in self.constraints.clone() {
+            if self.recover_constraint(a, b).is_err() {
+                crossing_constraints.push((a, b));
+            }
+        }
+        self.constraints
+            .retain(|constraint| !crossing_constraints.contains(constraint));
+
+        crossing_constraints
+    }
+
+    /// Forces the segment between the vertices `a` and `b` into the mesh and marks the edges
+    /// along it as constrained. The segment gets split at any vertex lying on it. Nothing
+    /// changes when the segment crosses a constrained edge.
+    fn recover_constraint(&mut self, a: usize, b: usize) -> Result<(), TriangulationError> {
+        let mut vertices_along = vec![a];
+        while let Some(&from) = vertices_along.last().filter(|from| **from != b) {
+            match self.trace_segment(from, b) {
+                Some(trace) if trace.crosses_constraint => {
+                    return Err(TriangulationError::CrossingConstraint(a, b));
+                }
+                Some(trace) => vertices_along.push(trace.end),
+                // One of the points is not in the mesh yet, like when all points are on a line.
+                None => return Ok(()),
+            }
+        }
+
+        for piece in vertices_along.windows(2) {
+            let (from, to) = (piece[0], piece[1]);
+            if let Some(trace) = self.trace_segment(from, to) {
+                self.triangulation_mesh
+                    .remove_triangles(&trace.crossed_triangles);
+                self.triangulate_pseudo_polygon(from, to, &trace.left_vertices);
+                let right_vertices: Vec<usize> = trace.right_vertices.into_iter().rev().collect();
+                self.triangulate_pseudo_polygon(to, from, &right_vertices);
+            }
+            self.triangulation_mesh.set_constrained(from, to, true);
+        }
+
+        Ok(())
+    }
+
+    /// Follows the segment from the vertex `from` towards the vertex `to` through the mesh, up
+    /// to the first vertex on it. `None` when either vertex is not in the mesh.
+    fn trace_segment(&self, from: usize, to: usize) -> Option<SegmentTrace> {
+        let mesh = &self.triangulation_mesh;
+        let (from_point, to_point) = (self.vertex(from), self.vertex(to));
+        let is_on_segment = |vertex_idx: usize| {
+            let vertex = self.vertex(vertex_idx);
+            let along = (vertex.x - from_point.x) * (to_point.x - from_point.x)
+                + (vertex.y - from_point.y) * (to_point.y - from_point.y);
+            orient2d(from_point, to_point, vertex) == 0. && along > 0.
+        };
+        let mut trace = SegmentTrace {
+            end: to,
+            ..Default::default()
+        };
+
+        // Find the triangle around `from` the segment leaves through.
+        let mut crossing = None;
+        for half_edge in mesh.vertex_out_edges(from) {
+            let (right, left) = (mesh.destination(half_edge), mesh.origin(half_edge.prev()));
+            if let Some(end) = [right, left]
+                .into_iter()
+                .find(|vertex_idx| *vertex_idx == to || is_on_segment(*vertex_idx))
+            {
+                trace.end = end;
+                return Some(trace);
+            }
+            if orient2d(from_point, self.vertex(right), to_point) > 0.
+                && orient2d(from_point, self.vertex(left), to_point) < 0.
+            {
+                crossing = Some(half_edge.next());
+            }
+        }
+        let mut crossing = crossing?;
+        trace.left_vertices.push(mesh.destination(crossing));
+        trace.right_vertices.push(mesh.origin(crossing));
+
+        // Each crossed edge runs from the right side of the segment to its left side.
+        for _ in 0..=mesh.len() {
+            trace.crossed_triangles.push(crossing.triangle);
+            if self.is_constrained_half_edge(crossing) {
+                trace.crosses_constraint = true;
+                return Some(trace);
+            }
+            let twin = mesh.twin(crossing)?;
+            let opposite = mesh.origin(twin.prev());
+            if opposite == to || is_on_segment(opposite) {
+                trace.crossed_triangles.push(twin.triangle);
+                trace.end = opposite;
+                return Some(trace);
+            }
+
+            if orient2d(from_point, to_point, self.vertex(opposite)) > 0. {
+                trace.left_vertices.push(opposite);
+                crossing = twin.next();
+            } else {
+                trace.right_vertices.push(opposite);
+                crossing = twin.prev();
+            }
+        }
+
+        None
+    }
+
+    /// Fills the region between the edge from `from` to `to` and `vertices`, which lie to the
+    /// left of it and are listed in order along the region's border from `from` to `to`. Each
+    /// step picks the vertex whose triangle with the edge has an empty circumcircle, and fills
+    /// the regions on both sides of that triangle the same way.
+    fn triangulate_pseudo_polygon(&mut self, from: usize, to: usize, vertices: &[usize]) {
+        if vertices.is_empty() {
+            return;
+        }
+
+        let mut apex = 0;
+        for candidate in 1..vertices.len() {
+            let triangle = self.triangle_points([from, to, vertices[apex]]);
+            if is_point_inside_circumcircle(triangle, self.vertex(vertices[candidate])) {
+                apex = candidate;
+            }
+        }
+
+        let apex_vertex = vertices[apex];
+        self.triangulate_pseudo_polygon(from, apex_vertex, &vertices[..apex]);
+        self.triangulate_pseudo_polygon(apex_vertex, to, &vertices[apex + 1..]);
+        self.add_triangle_to_mesh([from, to, apex_vertex]);
+    }
+
+    /// Removes the triangles outside the domain the constrained edges enclose, like the
+    /// triangles attached to the super triangle get stripped away at the end of a
+    /// triangulation. The regions between constrained edges get classified by flood filling from
+    /// the border of the mesh and counting the constrained edges crossed on the way there: the
+    /// outside and the insides of holes are an even number of crossings away, and get removed.
+    ///
+    /// The result is usually not convex anymore, which the other edits of a finished
+    /// triangulation do not expect, so this is meant to be the last step.
+    pub fn remove_triangles_outside_domain(&mut self) {
+        let mesh = &self.triangulation_mesh;
+        let mut crossings = vec![usize::MAX; mesh.len()];
+        // Spreading without crossing a constrained edge goes to the front of the queue, so
+        // triangles come out in order of how many constrained edges it takes to reach them.
+        let mut to_spread_from = VecDeque::new();
+        for triangle_idx in 0..mesh.len() {
+            for edge in 0..3 {
+                let half_edge = HalfEdge::new(triangle_idx, edge);
+                if mesh.twin(half_edge).is_none() {
+                    let crossed = usize::from(self.is_constrained_half_edge(half_edge));
+                    to_spread_from.push_back((triangle_idx, crossed));
+                }
+            }
+        }
+        to_spread_from
+            .make_contiguous()
+            .sort_by_key(|(_, crossed)| *crossed);
+
+        while let Some((triangle_idx, crossed)) = to_spread_from.pop_front() {
+            if crossings[triangle_idx] <= crossed {
+                continue;
+            }
+            crossings[triangle_idx] = crossed;
+
+            for (edge, neighbour) in mesh
+                .triangle_neighbours(triangle_idx)
+                .into_iter()
+                .enumerate()
+            {
+                let Some(neighbour) = neighbour else {
+                    continue;
+                };
+                if self.is_constrained_half_edge(HalfEdge::new(triangle_idx, edge)) {
+                    to_spread_from.push_back((neighbour, crossed + 1));
+                } else {
+                    to_spread_from.push_front((neighbour, crossed));
+                }
+            }
+        }
+
+        let triangles_to_remove: Vec<usize> = (0..mesh.len())
+            .filter(|triangle_idx| crossings[*triangle_idx] % 2 == 0)
+            .collect();
+        self.triangulation_mesh
+            .remove_triangles(&triangles_to_remove);
+    }
+
+    /// Runs the rest of the triangulation without stopping for draw updates. A point that is
+    /// half way through its animated insertion is finished off the animated way first.
+    pub fn finish_triangulation(&mut self) {
+        while !matches!(
+            self.state,
+            InternalState::Initial
+                | InternalState::GetBadTrianglesInMesh(0)
+                | InternalState::Finished
+        ) {
+            self.update_triangulation();
+        }
+        if self.state == InternalState::Finished {
+            return;
+        }
+
+        if self.state == InternalState::Initial {
+            self.add_super_triangle();
+        }
+        while let Some(point_idx) = self.current_point() {
+            let walk_end = self.locate_triangle(self.point_list[point_idx]);
+            self.insert_point_into_mesh(point_idx, walk_end);
+            self.current_point_idx += 1;
+        }
+        self.remove_triangles_attached_to_super_triangle();
+        self.clear_crap_to_plot();
+        self.state = InternalState::Finished;
+        self.recover_constraints();
+    }
+
+    /// Early return indicates a draw up\date is needed. Once the triangulation is finished, each
+    /// call takes one step of the refinement started by [`Self::start_refinement`], if any.
+    ///
+    /// Psuedocode reference:
+    /// function BowyerWatson (pointList)
+    ///     // pointList is a set of coordinates defining the points to be triangulated
+    ///     triangulation := empty triangle mesh data structure
+    ///     add super-triangle to triangulation // must be large enough to completely contain all the points in pointList
+    ///     for each point in pointList do // add all the points one at a time to the triangulation
+    ///         badTriangles := empty set
+    ///         for each triangle in triangulation do // first find all the triangles that are no longer valid due to the insertion
+    ///             if point is inside circumcircle of triangle
+    ///                 add triangle to badTriangles
+    ///         polygon := empty set
+    ///         for each triangle in badTriangles do // find the boundary of the polygonal hole
+    ///             for each edge in triangle do
+    ///                 if edge is not shared by any other triangles in badTriangles
+    ///                     add edge to polygon
+    ///         for each triangle in badTriangles do // remove them from the data structure
+    ///             remove triangle from triangulation
+    ///         for each edge in polygon do // re-triangulate the polygonal hole
+    ///             newTri := form a triangle from edge to point
+    ///             add newTri to triangulation
+    ///     for each triangle in triangulation // done inserting points, now clean up
+    ///         if triangle contains a vertex from original super-triangle
+    ///             remove triangle from triangulation
+    ///     return triangulation    
+    pub fn update_triangulation(&mut self) {
+        self.clear_crap_to_plot();
+        if self.state == InternalState::Finished {
+            self.update_refinement();
+            return;
+        }
+
+        if self.state == InternalState::Initial {
+            self.add_super_triangle();
+            self.state = InternalState::GetBadTrianglesInMesh(0);
+            return;
+        }
+
+        let Some(point_idx) = self.current_point() else {
+            self.remove_triangles_attached_to_super_triangle();
+            self.bad_triangles_to_plot = Default::default();
+            self.circumcircles_to_plot = Default::default();
+            self.state = InternalState::Finished;
+            self.recover_constraints();
+            return;
+        };
+        let point = self.point_list[point_idx];
+        let bad_triangles = self.get_all_bad_triangles_in_mesh_and_circumcircles_checked(point);
+        if self.state < InternalState::PolygonalHole {
+            return;
+        }
+        // The super triangle contains every point, so there are no border edges to see.
+        let polygon = self.polygonal_hole_boundary(&bad_triangles, &[]);
+        self.remove_all_bad_triangles_from_mesh(&bad_triangles);
+        if self.state <= InternalState::RemoveBadTrianglesFromMesh(true) {
+            self.polygon_for_new_triangles = polygon;
+            return;
+        }
+        let polygon = self.polygon_for_new_triangles.clone();
+        self.add_triangles_from_polygon_edges(&polygon, point_idx);
+        self.current_point_idx += 1;
+
+        self.state = InternalState::GetBadTrianglesInMesh(0);
+    }
+}
+
+/// Delauney refinement
+impl DelauneyTriangulationInformation {
+    /// Starts Ruppert's Delauney refinement, which adds points until no triangle has an angle
+    /// below `minimum_angle_degrees` or, if given, an area above `maximum_area`. Segments, that
+    /// is constrained edges and the border of the mesh, get split in the middle while a vertex
+    /// lies inside their diametral circle. Any other triangle missing the goal gets its
+    /// circumcenter inserted, unless that would encroach on a segment, which then gets split
+    /// instead. The new points are appended to the point list, and splitting a constrained edge
+    /// splits its constraint in [`Self::constraints`] too.
+    ///
+    /// Every call to [`Self::update_triangulation`] takes one step from here on, so the
+    /// refinement can be animated like the triangulation, and [`Self::finish_refinement`] runs
+    /// the rest in one go. A triangulation that is part way through gets finished first.
+    ///
+    /// The refinement is sure to end for minimum angles up to about 20.7 degrees, as long as no
+    /// two segments meet at an angle below 60 degrees. Next to smaller angles the goal can not
+    /// always be met, and edges there stop getting split once they are very short compared to
+    /// the whole point list.
+    pub fn start_refinement(&mut self, minimum_angle_degrees: f64, maximum_area: Option<f64>) {
+        self.finish_triangulation();
+
+        let (min, max) = bounding_box(&self.point_list).unwrap_or_default();
+        self.refinement = Some(Refinement {
+            minimum_angle: minimum_angle_degrees.to_radians(),
+            maximum_area,
+            shortest_edge: (max.x - min.x).max(max.y - min.y) * REFINEMENT_EDGE_TOLERANCE,
+            ..Default::default()
+        });
+        let mut encroached_segments = vec![];
+        let mut bad_triangles = vec![];
+        for (triangle_idx, triangle) in self.iter_triangles_in_mesh().enumerate() {
+            if self.needs_refinement(triangle) {
+                bad_triangles.push(triangle);
+            }
+            for edge in 0..3 {
+                let half_edge = HalfEdge::new(triangle_idx, edge);
+                if self.is_segment(half_edge) && self.is_segment_encroached(half_edge) {
+                    encroached_segments.push(self.triangulation_mesh.edge_vertices(half_edge));
+                }
+            }
+        }
+        if let Some(refinement) = &mut self.refinement {
+            refinement.encroached_segments = encroached_segments;
+            refinement.bad_triangles = bad_triangles;
+        }
+    }
+
+    /// Runs the rest of the refinement started by [`Self::start_refinement`] without stopping
+    /// for draw updates.
+    pub fn finish_refinement(&mut self) {
+        while self.update_refinement() {}
+        self.clear_crap_to_plot();
+    }
+
+    /// Refines the triangulation in one go, see [`Self::start_refinement`].
+    pub fn refine(&mut self, minimum_angle_degrees: f64, maximum_area: Option<f64>) {
+        self.start_refinement(minimum_angle_degrees, maximum_area);
+        self.finish_refinement();
+    }
+
+    #[must_use]
+    pub fn is_refining(&self) -> bool {
+        self.refinement.is_some()
+    }
+
+    /// Takes one refinement step, which splits a segment or a triangle. Returns false, and ends
+    /// the refinement, once there is nothing left to split.
+    fn update_refinement(&mut self) -> bool {
+        while let Some((a, b)) = self
+            .refinement
+            .as_mut()
+            .and_then(|refinement| refinement.encroached_segments.pop())
+        {
+            let Some(half_edge) = self.triangulation_mesh.half_edge(a, b) else {
+                continue;
+            };
+            if self.is_segment(half_edge)
+                && self.is_segment_encroached(half_edge)
+                && self.is_segment_splittable(half_edge)
+                && self.split_segment(half_edge)
+            {
+                return true;
+            }
+        }
+
+        while let Some(triangle) = self
+            .refinement
+            .as_mut()
+            .and_then(|refinement| refinement.bad_triangles.pop())
+        {
+            let Some(half_edge) = self
+                .triangulation_mesh
+                .half_edge(triangle[0], triangle[1])
+                .filter(|half_edge| {
+                    self.triangulation_mesh.destination(half_edge.next()) == triangle[2]
+                })
+            else {
+                continue;
+            };
+            if self.needs_refinement(triangle) && self.split_triangle(half_edge.triangle) {
+                return true;
+            }
+        }
+
+        self.refinement = None;
+        false
+    }
+
+    /// Whether `triangle` misses the goal of the refinement, leaving out triangles with an edge
+    /// too short to split.
+    fn needs_refinement(&self, triangle: [usize; 3]) -> bool {
+        let Some(refinement) = &self.refinement else {
+            return false;
+        };
+        let [a, b, c] = self.triangle_points(triangle);
+        let shortest_edge = euclidian_distance(a, b)
+            .min(euclidian_distance(b, c))
+            .min(euclidian_distance(c, a));
+        if shortest_edge < refinement.shortest_edge {
+            return false;
+        }
+
+        let (angle_a, angle_b, angle_c) = calculate_angles_of_triangle([a, b, c]);
+        let area = polygon_area(&[a, b, c]);
+        angle_a.min(angle_b).min(angle_c) < refinement.minimum_angle
+            || refinement
+                .maximum_area
+                .is_some_and(|maximum_area| area > maximum_area)
+    }
+
+    /// Whether the half-edge lies on the border of the mesh or on a constrained edge, which the
+    /// refinement only ever splits in the middle.
+    fn is_segment(&self, half_edge: HalfEdge) -> bool {
+        self.triangulation_mesh.twin(half_edge).is_none()
+            || self.is_constrained_half_edge(half_edge)
+    }
+
+    /// Whether `point` lies strictly inside the circle with the edge from `a` to `b` as its
+    /// diameter, which is when the edge is seen from the point at an angle over 90 degrees.
+    fn is_inside_diametral_circle(&self, a: usize, b: usize, point: Point) -> bool {
+        let (a, b) = (self.vertex(a) - point, self.vertex(b) - point);
+        a.x * b.x + a.y * b.y < 0.
+    }
+
+    /// Whether the vertex across the segment, on either side, lies inside its diametral circle.
+    fn is_segment_encroached(&self, half_edge: HalfEdge) -> bool {
+        let (a, b) = self.triangulation_mesh.edge_vertices(half_edge);
+        std::iter::once(half_edge)
+            .chain(self.triangulation_mesh.twin(half_edge))
+            .any(|half_edge| {
+                let apex = self.triangulation_mesh.origin(half_edge.prev());
+                self.is_inside_diametral_circle(a, b, self.vertex(apex))
+            })
+    }
+
+    fn is_segment_splittable(&self, half_edge: HalfEdge) -> bool {
+        let Some(refinement) = &self.refinement else {
+            return false;
+        };
+        let (a, b) = self.triangulation_mesh.edge_vertices(half_edge);
+        euclidian_distance(self.vertex(a), self.vertex(b)) >= 2. * refinement.shortest_edge
+    }
+
+    /// Inserts the middle of a segment, keeping the halves constrained if it was. Returns false
+    /// if there is a point in the middle already.
+    fn split_segment(&mut self, half_edge: HalfEdge) -> bool {
+        let (a, b) = self.triangulation_mesh.edge_vertices(half_edge);
+        let (a_point, b_point) = (self.vertex(a), self.vertex(b));
+        let middle = (a_point + b_point) / 2.;
+        self.circumcircles_to_plot.push(Circle::new(
+            middle,
+            euclidian_distance(a_point, b_point) / 2.,
+        ));
+
+        let middle_value = self
+            .values
+            .get(a)
+            .zip(self.values.get(b))
+            .map(|(a_value, b_value)| (a_value + b_value) / 2.);
+        let Some(middle_idx) = self.add_refinement_point(middle, middle_value) else {
+            return false;
+        };
+
+        // Rounding can put the middle just off the segment, so instead of locating it, the
+        // triangles on either side get split at it and then flipped back into shape.
+        let sides: Vec<HalfEdge> = std::iter::once(half_edge)
+            .chain(self.triangulation_mesh.twin(half_edge))
+            .collect();
+        let mut new_triangles = vec![];
+        let mut edges_to_legalize = vec![];
+        for side in &sides {
+            let (origin, destination) = self.triangulation_mesh.edge_vertices(*side);
+            let apex = self.triangulation_mesh.origin(side.prev());
+            new_triangles.extend([[origin, middle_idx, apex], [middle_idx, destination, apex]]);
+            edges_to_legalize.extend([(destination, apex), (apex, origin)]);
+        }
+        let is_constrained = self.is_constrained_half_edge(half_edge);
+        self.triangulation_mesh.set_constrained(a, b, false);
+        let side_triangles: Vec<usize> = sides.iter().map(|side| side.triangle).collect();
+        self.triangulation_mesh.remove_triangles(&side_triangles);
+        for new_triangle in new_triangles {
+            self.add_triangle_to_mesh(new_triangle);
+        }
+        if is_constrained {
+            self.triangulation_mesh.set_constrained(a, middle_idx, true);
+            self.triangulation_mesh.set_constrained(middle_idx, b, true);
+            self.split_constraint(a, b, middle_idx);
+        }
+        self.legalize_edges(edges_to_legalize);
+        self.queue_refinement_around(middle_idx);
+
+        true
+    }
+
+    /// Inserts the circumcenter of the triangle, or splits a segment it would encroach on
+    /// instead. Returns false when neither is possible.
+    fn split_triangle(&mut self, triangle_idx: usize) -> bool {
+        let triangle = self.triangulation_mesh.triangles()[triangle_idx];
+        let triangle_points = self.triangle_points(triangle);
+        let circumcircle = Circle::from(triangle_points);
+        let center = circumcircle.center();
+
+        let walk = self.walk_within_segments(triangle_idx, center);
+        let encroached_segments: Vec<HalfEdge> = match walk {
+            // A circumcenter beyond a segment is inside its diametral circle too.
+            Err(segment) => vec![segment],
+            Ok(center_triangle) => {
+                let walk_end = Some(WalkEnd::Triangle(center_triangle));
+                let (bad_triangles, _) = self.get_all_bad_triangles_in_mesh(center, walk_end);
+                bad_triangles
+                    .into_iter()
+                    .flat_map(|triangle_idx| {
+                        (0..3).map(move |edge| HalfEdge::new(triangle_idx, edge))
+                    })
+                    .filter(|half_edge| {
+                        let (a, b) = self.triangulation_mesh.edge_vertices(*half_edge);
+                        self.is_segment(*half_edge) && self.is_inside_diametral_circle(a, b, center)
+                    })
+                    .collect()
+            }
+        };
+        if let (Ok(center_triangle), true) = (walk, encroached_segments.is_empty()) {
+            let center_value = self.value_for_new_point(center);
+            let Some(center_idx) = self.add_refinement_point(center, center_value) else {
+                return false;
+            };
+            self.insert_point_into_mesh(center_idx, Some(WalkEnd::Triangle(center_triangle)));
+            self.bad_triangles_to_plot.push(triangle_points);
+            self.circumcircles_to_plot.push(circumcircle);
+            self.queue_refinement_around(center_idx);
+            return true;
+        }
+
+        let Some(segment) = encroached_segments
+            .into_iter()
+            .find(|half_edge| self.is_segment_splittable(*half_edge))
+        else {
+            return false;
+        };
+        if !self.split_segment(segment) {
+            return false;
+        }
+        self.bad_triangles_to_plot.push(triangle_points);
+        if let Some(refinement) = &mut self.refinement {
+            // The triangle goes back on the queue, in case it is still there after the split.
+            refinement.bad_triangles.push(triangle);
+        }
+
+        true
+    }
+
+    /// Walks along the straight line from the middle of a triangle to `point`, returning the
+    /// triangle containing the point, or the first segment the line crosses on the way there.
+    /// Unlike [`Self::locate_triangle`], this never leaves the region the segments enclose,
+    /// even where the mesh is not convex.
+    fn walk_within_segments(&self, triangle_idx: usize, point: Point) -> Result<usize, HalfEdge> {
+        let [a, b, c] = self.triangle_points(self.triangulation_mesh.triangles()[triangle_idx]);
+        let start = (a + b + c) / 3.;
+        let mut triangle_idx = triangle_idx;
+
+        'walk: for _ in 0..=self.triangulation_mesh.len() {
+            for edge in 0..3 {
+                let half_edge = HalfEdge::new(triangle_idx, edge);
+                let (origin, destination) = self.triangulation_mesh.edge_vertices(half_edge);
+                let (origin, destination) = (self.vertex(origin), self.vertex(destination));
+                // The line leaves through the edge that has the point beyond it, with the
+                // edge's origin on the right of the line and its destination on the left.
+                if orient2d(origin, destination, point) < 0.
+                    && orient2d(start, point, origin) <= 0.
+                    && orient2d(start, point, destination) > 0.
+                {
+                    match self.triangulation_mesh.twin(half_edge) {
+                        Some(twin) if !self.is_constrained_half_edge(half_edge) => {
+                            triangle_idx = twin.triangle;
+                        }
+                        _ => return Err(half_edge),
+                    }
+                    continue 'walk;
+                }
+            }
+
+            return Ok(triangle_idx);
+        }
+
+        Ok(triangle_idx)
+    }
+
+    /// Adds a point the refinement creates to the point list with its value, unless there is a
+    /// point at its position already. Returns its index in the point list, for putting it into
+    /// the mesh.
+    fn add_refinement_point(&mut self, point: Point, value: Option<f64>) -> Option<usize> {
+        let point_idx = self.point_list.len();
+        let Entry::Vacant(entry) = self.point_indices_by_position.entry(point.to_bits()) else {
+            return None;
+        };
+        entry.insert(point_idx);
+        self.values.extend(value);
+        self.point_list.push(point);
+        self.insertion_order.push(point_idx);
+        self.current_point_idx += 1;
+
+        Some(point_idx)
+    }
+
+    /// Queues up the triangles around a new vertex that miss the goal, and the segments the
+    /// vertex encroaches on, which are all edges of those triangles.
+    fn queue_refinement_around(&mut self, vertex: usize) {
+        let mut encroached_segments = vec![];
+        let mut bad_triangles = vec![];
+        for out_edge in self.triangulation_mesh.vertex_out_edges(vertex) {
+            let triangle = self.triangulation_mesh.triangles()[out_edge.triangle];
+            if self.needs_refinement(triangle) {
+                bad_triangles.push(triangle);
+            }
+            for half_edge in [out_edge, out_edge.next(), out_edge.prev()] {
+                if self.is_segment(half_edge) && self.is_segment_encroached(half_edge) {
+                    encroached_segments.push(self.triangulation_mesh.edge_vertices(half_edge));
+                }
+            }
+        }
+        if let Some(refinement) = &mut self.refinement {
+            refinement.encroached_segments.extend(encroached_segments);
+            refinement.bad_triangles.extend(bad_triangles);
+        }
+    }
+
+    /// Replaces the constraint running along the constrained edge from `a` to `b` by its pieces
+    /// before, along and after the edge, which now runs through `middle`. The middle can be
+    /// just off the constraint's line, so the pieces next to it have to be constraints of their
+    /// own to keep matching the mesh.
+    fn split_constraint(&mut self, a: usize, b: usize, middle: usize) {
+        let is_on_constraint = |vertex: usize, (from, to): (usize, usize)| {
+            let (vertex_point, from_point, to_point) =
+                (self.vertex(vertex), self.vertex(from), self.vertex(to));
+            let (offset, direction) = (vertex_point - from_point, to_point - from_point);
+            let distance_along = offset.x * direction.x + offset.y * direction.y;
+            vertex == from
+                || vertex == to
+                || (orient2d(from_point, to_point, vertex_point) == 0.
+                    && distance_along > 0.
+                    && distance_along < direction.x * direction.x + direction.y * direction.y)
+        };
+        let Some(constraint_idx) = self.constraints.iter().position(|constraint| {
+            is_on_constraint(a, *constraint) && is_on_constraint(b, *constraint)
+        }) else {
+            return;
+        };
+
+        let (from, to) = self.constraints[constraint_idx];
+        let from_point = self.vertex(from);
+        let (near, far) = if euclidian_distance(from_point, self.vertex(a))
+            < euclidian_distance(from_point, self.vertex(b))
+        {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let pieces: Vec<(usize, usize)> = [(from, near), (near, middle), (middle, far), (far, to)]
+            .into_iter()
+            .filter(|(start, end)| start != end)
+            .collect();
+        self.constraints
+            .splice(constraint_idx..=constraint_idx, pieces);
+    }
+}
+
+/// Point queries
+impl DelauneyTriangulationInformation {
+    /// Triangle of the mesh containing `point`, as its index into [`TriangleMesh::triangles`],
+    /// together with the barycentric coordinates of the point, one weight per corner of the
+    /// triangle. Points on an edge belong to either triangle next to it, and points outside
+    /// the mesh give `None`.
+    ///
+    /// The triangle is found by walking through the mesh towards the point. Where the walk
+    /// cannot get there, because the point is beyond a hole or the mesh is no longer Delauney
+    /// after inserting constraints, the triangles get checked one by one instead.
+    #[must_use]
+    pub fn locate(&self, point: Point) -> Option<(usize, [f64; 3])> {
+        let triangles = self.triangulation_mesh.triangles();
+        let triangle_idx = match self.locate_triangle(point) {
+            Some(WalkEnd::Triangle(triangle_idx)) => triangle_idx,
+            _ => (0..triangles.len()).find(|triangle_idx| {
+                get_edges_from_triangle(self.triangle_points(triangles[*triangle_idx]))
+                    .iter()
+                    .all(|(origin, destination)| orient2d(*origin, *destination, point) >= 0.)
+            })?,
+        };
+
+        Some((
+            triangle_idx,
+            barycentric_coordinates(self.triangle_points(triangles[triangle_idx]), point),
+        ))
+    }
+
+    /// Index into the point list of the point closest to `point`, or `None` when there are no
+    /// points. Merged duplicates are never returned, only the point they were merged into.
+    ///
+    /// Starting from the triangle the walk towards `point` ends at, this keeps moving to a
+    /// neighbouring vertex closer to `point`. In a Delauney triangulation the vertex where that
+    /// stops is the closest one, and once constraints are in it is close to it.
+    #[must_use]
+    pub fn nearest_vertex(&self, point: Point) -> Option<usize> {
+        let mesh = &self.triangulation_mesh;
+        let distance_to_point =
+            |vertex_idx: usize| euclidian_distance(self.vertex(vertex_idx), point);
+        let closest = |vertices: &[usize]| {
+            vertices
+                .iter()
+                .copied()
+                .filter(|vertex_idx| !SUPER_TRIANGLE_VERTICES.contains(vertex_idx))
+                .min_by(|a, b| distance_to_point(*a).total_cmp(&distance_to_point(*b)))
+        };
+        let mut nearest = match self.locate_triangle(point) {
+            Some(WalkEnd::Triangle(triangle_idx)) => closest(&mesh.triangles()[triangle_idx]),
+            Some(WalkEnd::OutsideMesh(half_edge)) => {
+                let (origin, destination) = mesh.edge_vertices(half_edge);
+                closest(&[origin, destination])
+            }
+            // Without triangles the points are all on one line, with nothing to walk through,
+            // so they all get checked.
+            None => closest(&self.insertion_order),
+        }?;
+
+        while let Some(closer) = closest(&mesh.vertex_neighbours(nearest))
+            .filter(|neighbour| distance_to_point(*neighbour) < distance_to_point(nearest))
+        {
+            nearest = closer;
+        }
+
+        Some(nearest)
+    }
+
+    /// Sibson's natural neighbour coordinates of `point`, as pairs of an index into the point
+    /// list and its weight, which add up to one. Each weight is the share of the area the point
+    /// would take from that point's Voronoi cell if it was inserted, so only the natural
+    /// neighbours, the points around the cavity inserting it would open, show up. `None`
+    /// outside the mesh and on its border, where the cell of the point would be unbounded.
+    ///
+    /// The point is not inserted: the cavity is found like for an insertion and the areas are
+    /// worked out from circumcenters around it. This is meant for a finished Delauney
+    /// triangulation without constraints.
+    #[must_use]
+    pub fn natural_neighbour_weights(&self, point: Point) -> Option<Vec<(usize, f64)>> {
+        if let Some(point_idx) = self.point_indices_by_position.get(&point.to_bits()) {
+            return Some(vec![(*point_idx, 1.)]);
+        }
+        let (bad_triangles, visible_border_edges) =
+            self.get_all_bad_triangles_in_mesh(point, self.locate_triangle(point));
+        if bad_triangles.is_empty() || !visible_border_edges.is_empty() {
+            return None;
+        }
+
+        let mesh = &self.triangulation_mesh;
+        let bad_triangle_set: IndexHashSet<usize> = bad_triangles.iter().copied().collect();
+        let hole_boundary = self.polygonal_hole_boundary(&bad_triangles, &[]);
+        let previous_on_boundary: IndexHashMap<usize, usize> = hole_boundary
+            .iter()
+            .map(|(origin, destination)| (*destination, *origin))
+            .collect();
+        let mut weights = vec![];
+        for (neighbour, next) in hole_boundary {
+            let previous = previous_on_boundary[&neighbour];
+            // The area taken from the neighbour's cell lies between the new Voronoi edge
+            // between the two of them and the old Voronoi vertices around the neighbour, which
+            // are the circumcenters of the bad triangles at it, from the boundary edge to the
+            // next neighbour around to the one from the previous neighbour.
+            let mut taken_area = vec![circumcenter_of_triangle([
+                point,
+                self.vertex(neighbour),
+                self.vertex(next),
+            ])];
+            let mut half_edge = mesh.half_edge(neighbour, next)?;
+            loop {
+                let triangle = mesh.triangles()[half_edge.triangle];
+                taken_area.push(circumcenter_of_triangle(self.triangle_points(triangle)));
+                match mesh
+                    .twin(half_edge.prev())
+                    .filter(|twin| bad_triangle_set.contains(&twin.triangle))
+                {
+                    Some(twin) => half_edge = twin,
+                    None => break,
+                }
+            }
+            taken_area.push(circumcenter_of_triangle([
+                point,
+                self.vertex(previous),
+                self.vertex(neighbour),
+            ]));
+            weights.push((neighbour, polygon_area(&taken_area).abs()));
+        }
+
+        let total_area: f64 = weights.iter().map(|(_, area)| area).sum();
+        for (_, weight) in &mut weights {
+            *weight /= total_area;
+        }
+
+        Some(weights)
+    }
+}
+
+/// Constrained Delauney triangulation of the polygon `outer_boundary` with `holes` cut out of
+/// it, keeping only the triangles inside. Each polygon is a list of its corners, and the point
+/// list of the result holds the corners of `outer_boundary` followed by those of each hole in
+/// turn. Polygon edges crossing each other are refused.
+pub fn triangulate_polygon(
+    outer_boundary: Vec<Point>,
+    holes: Vec<Vec<Point>>,
+) -> Result<DelauneyTriangulationInformation, TriangulationError> {
+    let mut polygon_edges = vec![];
+    let mut point_list = vec![];
+    for polygon in std::iter::once(outer_boundary).chain(holes) {
+        let first_corner = point_list.len();
+        for corner in 0..polygon.len() {
+            let next_corner = (corner + 1) % polygon.len();
+            polygon_edges.push((first_corner + corner, first_corner + next_corner));
+        }
+        point_list.extend(polygon);
+    }
+
+    let mut delauney_triangulation_information = triangulate(point_list)?;
+    for (a, b) in polygon_edges {
+        delauney_triangulation_information.add_constraint(a, b)?;
+    }
+    delauney_triangulation_information.remove_triangles_outside_domain();
+
+    Ok(delauney_triangulation_information)
+}
+
+/// Delauney triangulation of `point_list` computed in one go, for when the animation is not
+/// needed. The mesh is available through [`DelauneyTriangulationInformation::mesh`]. Points are
+/// inserted in [`PointOrdering::BiasedRandomized`] order, which keeps large inputs fast.
+pub fn triangulate(
+    point_list: Vec<Point>,
+) -> Result<DelauneyTriangulationInformation, TriangulationError> {
+    let mut delauney_triangulation_information = DelauneyTriangulationInformation::default();
+    delauney_triangulation_information.set_point_ordering(PointOrdering::BiasedRandomized);
+    delauney_triangulation_information.set_point_list(point_list, None)?;
+    delauney_triangulation_information.finish_triangulation();
+
+    Ok(delauney_triangulation_information)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that no triangle has the far corner of a neighbour inside its circumcircle, across
+    /// every edge that is not constrained.
+    fn assert_locally_delauney(triangulation: &DelauneyTriangulationInformation) {
+        let mesh = triangulation.mesh();
+        for triangle_idx in 0..mesh.len() {
+            for edge in 0..3 {
+                let half_edge = HalfEdge::new(triangle_idx, edge);
+                let Some(twin) = mesh.twin(half_edge) else {
+                    continue;
+                };
+                let (origin, destination) = mesh.edge_vertices(half_edge);
+                if mesh.is_constrained(origin, destination) {
+                    continue;
+                }
+                let triangle = triangulation.triangle_points(mesh.triangles()[triangle_idx]);
+                let far_corner = triangulation.vertex(mesh.origin(twin.prev()));
+                assert!(
+                    !is_point_inside_circumcircle(triangle, far_corner),
+                    "the edge ({origin}, {destination}) is not locally Delauney"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn grid_is_locally_delauney() {
+        let grid: Vec<Point> = (0..20)
+            .flat_map(|x| (0..20).map(move |y| Point::new(x as f64, y as f64)))
+            .collect();
+        let triangulation = triangulate(grid).unwrap();
+
+        assert_eq!(triangulation.mesh().len(), 2 * 19 * 19);
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn nearly_collinear_points_get_triangulated() {
+        let flat_triangle = vec![
+            Point::new(0., 0.),
+            Point::new(1000., 0.),
+            Point::new(500., 0.01),
+        ];
+        assert_eq!(triangulate(flat_triangle.clone()).unwrap().mesh().len(), 1);
+
+        let mut animated = DelauneyTriangulationInformation::default();
+        animated.set_point_list(flat_triangle, None).unwrap();
+        while !animated.is_finished() {
+            animated.update_triangulation();
+        }
+        assert_eq!(animated.mesh().len(), 1);
+
+        // Every point of the arc is on the convex hull.
+        let arc: Vec<Point> = (0..100)
+            .map(|x| Point::new(x as f64, 1e-6 * (x as f64 - 50.).powi(2)))
+            .collect();
+        let triangulation = triangulate(arc).unwrap();
+        assert_eq!(triangulation.mesh().len(), 98);
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn extreme_coordinates_get_triangulated() {
+        let points: Vec<Point> = (0..60)
+            .map(|point_idx| {
+                Point::new((point_idx * 37 % 101) as f64, (point_idx * 59 % 103) as f64)
+            })
+            .collect();
+        let triangle_count = triangulate(points.clone()).unwrap().mesh().len();
+
+        // Powers of two from about 1e-300 to 1e300, which scale the points exactly.
+        for scale in [-1000, -266, 249, 1000].map(|exponent| 2_f64.powi(exponent)) {
+            let triangulation =
+                triangulate(points.iter().map(|point| *point * scale).collect()).unwrap();
+            assert_eq!(
+                triangulation.mesh().len(),
+                triangle_count,
+                "scaled by {scale:e}"
+            );
+            assert_locally_delauney(&triangulation);
+        }
+    }
+
+    #[test]
+    fn a_mesh_in_pieces_gets_joined() {
+        let mut triangulation = DelauneyTriangulationInformation::default();
+        triangulation
+            .set_point_list(
+                [
+                    (0., 0.),
+                    (1., 0.),
+                    (0., 1.),
+                    (10., 0.),
+                    (11., 0.),
+                    (10., 1.),
+                ]
+                .map(Point::from)
+                .to_vec(),
+                None,
+            )
+            .unwrap();
+        // Two triangles far apart, as if the triangles joining them had all touched the super
+        // triangle.
+        triangulation.current_point_idx = triangulation.insertion_order().len();
+        triangulation.add_triangle_to_mesh([0, 1, 2]);
+        triangulation.add_triangle_to_mesh([3, 4, 5]);
+        triangulation.fill_border_dents();
+
+        assert_eq!(triangulation.border_loops().len(), 1);
+        assert_eq!(triangulation.mesh().len(), 4);
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn constraints_show_up_as_mesh_edges() {
+        let points: Vec<Point> = (0..80)
+            .map(|point_idx| {
+                Point::new(
+                    (point_idx * 37 % 101) as f64 + 0.013 * point_idx as f64,
+                    (point_idx * 59 % 103) as f64,
+                )
+            })
+            .collect();
+        // Joining the points from left to right gives a path that never crosses itself, with
+        // long segments cutting through the mesh.
+        let mut from_left_to_right: Vec<usize> = (0..points.len()).collect();
+        from_left_to_right.sort_by(|a, b| points[*a].x.total_cmp(&points[*b].x));
+        let path: Vec<(usize, usize)> = from_left_to_right
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+
+        let mut triangulation = DelauneyTriangulationInformation::default();
+        triangulation.set_point_list(points.clone(), None).unwrap();
+        triangulation.set_constraints(path[..40].to_vec()).unwrap();
+        triangulation.finish_triangulation();
+        for (a, b) in &path[40..] {
+            triangulation.add_constraint(*a, *b).unwrap();
+        }
+
+        assert_eq!(triangulation.constraints(), path);
+        for (a, b) in &path {
+            assert!(triangulation.mesh().is_constrained(*a, *b), "({a}, {b})");
+        }
+        assert_eq!(triangulation.mesh().constrained_edges().count(), path.len());
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn crossing_constraints_are_refused() {
+        let square = [
+            (0., 0.),
+            (10., 0.),
+            (10., 10.),
+            (0., 10.),
+            (3., 6.),
+            (6., 2.),
+        ]
+        .map(Point::from)
+        .to_vec();
+        let mut triangulation = triangulate(square).unwrap();
+        triangulation.add_constraint(0, 2).unwrap();
+
+        assert_eq!(
+            triangulation.add_constraint(1, 3),
+            Err(TriangulationError::CrossingConstraint(1, 3))
+        );
+        assert_eq!(triangulation.constraints(), [(0, 2)]);
+        assert!(triangulation.mesh().is_constrained(0, 2));
+        assert!(triangulation.mesh().half_edge(1, 3).is_none());
+        assert_locally_delauney(&triangulation);
+
+        triangulation.set_constraints(vec![(4, 5), (0, 2)]).unwrap();
+        assert_eq!(triangulation.constraints(), [(4, 5)]);
+        assert_eq!(
+            triangulation.mesh().constrained_edges().collect::<Vec<_>>(),
+            [(4, 5)]
+        );
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn moving_a_point_across_a_constraint_drops_the_crossing_constraint() {
+        let mut triangulation = triangulate(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+            Point::new(7., 2.),
+        ])
+        .unwrap();
+        triangulation.add_constraint(0, 2).unwrap();
+        triangulation.add_constraint(1, 4).unwrap();
+
+        assert_eq!(
+            triangulation.move_point(4, Point::new(3., 8.)),
+            Ok(vec![(1, 4)])
+        );
+        assert_eq!(triangulation.vertex(4), Point::new(3., 8.));
+        assert_eq!(triangulation.constraints(), [(0, 2)]);
+        assert_eq!(
+            triangulation.mesh().constrained_edges().collect::<Vec<_>>(),
+            [(0, 2)]
+        );
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn refinement_meets_the_minimum_angle_and_maximum_area() {
+        let mut points = [(0., 0.), (100., 0.), (100., 100.), (0., 100.)]
+            .map(Point::from)
+            .to_vec();
+        points.extend((1..20).map(|point_idx| {
+            Point::new(
+                (point_idx * 37 % 97) as f64,
+                (point_idx * 61 % 89) as f64 + 5.,
+            )
+        }));
+        let mut triangulation = triangulate(points).unwrap();
+        triangulation.refine(20., Some(50.));
+
+        for triangle in triangulation.mesh().triangles() {
+            let [a, b, c] = triangulation.triangle_points(*triangle);
+            let (angle_a, angle_b, angle_c) = calculate_angles_of_triangle([a, b, c]);
+            assert!(angle_a.min(angle_b).min(angle_c).to_degrees() >= 20. - 1e-9);
+            assert!(polygon_area(&[a, b, c]) <= 50.);
+        }
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn interpolation_works_for_extreme_coordinates() {
+        let scale = 2_f64.powi(600);
+        let corners = [(0., 0.), (8., 0.), (0., 8.), (8., 8.)].map(Point::from);
+        let mut triangulation = DelauneyTriangulationInformation::default();
+        triangulation
+            .set_point_list(
+                corners.map(|corner| corner * scale).to_vec(),
+                Some(corners.map(|corner| corner.x + 2. * corner.y).to_vec()),
+            )
+            .unwrap();
+        triangulation.finish_triangulation();
+
+        assert_eq!(
+            triangulation.interpolate(Point::new(1., 2.) * scale),
+            Some(5.)
+        );
+    }
+
+    #[test]
+    fn natural_neighbour_weights_reproduce_linear_functions() {
+        let points: Vec<Point> = (0..80)
+            .map(|point_idx| {
+                Point::new((point_idx * 37 % 101) as f64, (point_idx * 59 % 103) as f64)
+            })
+            .collect();
+        let linear = |point: Point| 3. * point.x - 2. * point.y + 5.;
+        let values = points.iter().map(|point| linear(*point)).collect();
+        let mut triangulation = DelauneyTriangulationInformation::default();
+        triangulation.set_point_list(points, Some(values)).unwrap();
+        triangulation.finish_triangulation();
+
+        for query_idx in 0..50 {
+            let point = Point::new(
+                20. + (query_idx * 13 % 61) as f64 + 0.37,
+                20. + (query_idx * 29 % 59) as f64 + 0.61,
+            );
+            let weights = triangulation.natural_neighbour_weights(point).unwrap();
+
+            let total_weight: f64 = weights.iter().map(|(_, weight)| weight).sum();
+            assert!(
+                (total_weight - 1.).abs() < 1e-12,
+                "{total_weight} at {point:?}"
+            );
+            let weighted_position = weights
+                .iter()
+                .fold(Point::default(), |sum, (point_idx, weight)| {
+                    sum + triangulation.vertex(*point_idx) * *weight
+                });
+            assert!(
+                euclidian_distance(weighted_position, point) < 1e-9,
+                "{weighted_position:?} instead of {point:?}"
+            );
+            let value = triangulation.natural_neighbour_interpolate(point).unwrap();
+            assert!((value - linear(point)).abs() < 1e-9, "{value} at {point:?}");
+        }
+    }
+
+    #[test]
+    fn constraints_move_with_a_point_that_had_duplicates() {
+        let mut triangulation = triangulate(vec![
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+            Point::new(7., 2.),
+            Point::new(7., 2.),
+        ])
+        .unwrap();
+        triangulation.add_constraint(3, 4).unwrap();
+
+        assert_eq!(triangulation.move_point(4, Point::new(8., 6.)), Ok(vec![]));
+        assert_eq!(triangulation.constraints(), [(3, 4)]);
+        assert!(triangulation.mesh().is_constrained(3, 4));
+        assert!(triangulation.mesh().half_edge(5, 1).is_some());
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn polygon_with_a_hole_gets_triangulated_inside() {
+        let outer_boundary = [
+            (0., 0.),
+            (100., 0.),
+            (100., 40.),
+            (40., 40.),
+            (40., 100.),
+            (0., 100.),
+        ]
+        .map(Point::from)
+        .to_vec();
+        let hole = [(10., 10.), (30., 10.), (30., 30.), (10., 30.)]
+            .map(Point::from)
+            .to_vec();
+        let expected_area = polygon_area(&outer_boundary) - polygon_area(&hole);
+        let triangulation = triangulate_polygon(outer_boundary, vec![hole]).unwrap();
+
+        let mut area = 0.;
+        for triangle in triangulation.mesh().triangles() {
+            let [a, b, c] = triangulation.triangle_points(*triangle);
+            area += polygon_area(&[a, b, c]);
+            let centroid = (a + b + c) / 3.;
+            assert!(
+                !(10. < centroid.x && centroid.x < 30. && 10. < centroid.y && centroid.y < 30.),
+                "a triangle around {centroid:?} is in the hole"
+            );
+        }
+        assert!(
+            (area - expected_area).abs() < 1e-9,
+            "{area} instead of {expected_area}"
+        );
+        assert_locally_delauney(&triangulation);
+    }
+
+    #[test]
+    fn setting_a_new_point_list_starts_over() {
+        let circle = |point_count: usize| -> Vec<Point> {
+            (0..point_count)
+                .map(|point_idx| {
+                    let angle = point_idx as f64 / point_count as f64 * std::f64::consts::TAU;
+                    Point::new(angle.cos(), angle.sin()) * 100.
+                })
+                .collect()
+        };
+        let mut triangulation = triangulate(circle(50)).unwrap();
+        triangulation.add_constraint(0, 25).unwrap();
+
+        triangulation.set_point_list(circle(5), None).unwrap();
+        assert!(!triangulation.is_finished());
+        assert!(triangulation.mesh().is_empty());
+        assert!(triangulation.constraints().is_empty());
+
+        triangulation.finish_triangulation();
+        assert_eq!(triangulation.mesh().len(), 3);
+        assert!(triangulation
+            .mesh()
+            .triangles()
+            .iter()
+            .flatten()
+            .all(|point_idx| *point_idx < 5));
     }
 }
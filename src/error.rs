@@ -0,0 +1,37 @@
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriangulationError {
+    /// The point at this index of the point list has a NaN or infinite coordinate.
+    NonFiniteCoordinate(usize),
+    /// There is no point at this index of the point list.
+    PointIndexOutOfBounds(usize),
+    /// The constraint between the points at these indices crosses another constraint.
+    CrossingConstraint(usize, usize),
+    /// There are this many values for the second number of points.
+    ValueCountMismatch(usize, usize),
+}
+
+impl fmt::Display for TriangulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TriangulationError::NonFiniteCoordinate(point_idx) => {
+                write!(f, "point {point_idx} has a NaN or infinite coordinate")
+            }
+            TriangulationError::PointIndexOutOfBounds(point_idx) => {
+                write!(f, "there is no point {point_idx}")
+            }
+            TriangulationError::CrossingConstraint(a, b) => {
+                write!(
+                    f,
+                    "the constraint between points {a} and {b} crosses another constraint"
+                )
+            }
+            TriangulationError::ValueCountMismatch(value_count, point_count) => {
+                write!(f, "there are {value_count} values for {point_count} points")
+            }
+        }
+    }
+}
+
+impl Error for TriangulationError {}
@@ -0,0 +1,146 @@
+#[cfg(feature = "gui")]
+use sfml::graphics::{Color, PrimitiveType, RenderStates, RenderTarget, RenderWindow, Vertex};
+
+use crate::{
+    circle::Circle, delauney_triangulation::DelauneyTriangulationInformation, mesh::HalfEdge,
+};
+
+impl DelauneyTriangulationInformation {
+    /// Indices into the point list of the points on the convex hull, in counterclockwise order
+    /// starting at the leftmost one, which is the border of a finished triangulation. Points
+    /// lying on a hull edge are included. When all points lie on one line the hull is the two
+    /// points at its ends, or the single point if there is only one.
+    ///
+    /// After [`Self::remove_triangles_outside_domain`] the border of the mesh is not convex
+    /// anymore, and this follows the part of it through the leftmost point.
+    #[must_use]
+    pub fn convex_hull(&self) -> Vec<usize> {
+        let mesh = self.mesh();
+        let border_edges: Vec<HalfEdge> = (0..mesh.len())
+            .flat_map(|triangle_idx| (0..3).map(move |edge| HalfEdge::new(triangle_idx, edge)))
+            .filter(|half_edge| mesh.twin(*half_edge).is_none())
+            .collect();
+        let Some(&leftmost) = self
+            .points_sorted_along_line(border_edges.iter().map(|half_edge| mesh.origin(*half_edge)))
+            .first()
+        else {
+            // Without triangles the points are all on one line, with the hull at its ends.
+            let points_along_line =
+                self.points_sorted_along_line(self.insertion_order().iter().copied());
+            return match (points_along_line.first(), points_along_line.last()) {
+                (Some(leftmost), Some(rightmost)) if leftmost != rightmost => {
+                    vec![*leftmost, *rightmost]
+                }
+                (Some(leftmost), _) => vec![*leftmost],
+                _ => vec![],
+            };
+        };
+        let start = *border_edges
+            .iter()
+            .find(|half_edge| mesh.origin(**half_edge) == leftmost)
+            .expect("the leftmost point was taken from the border edges");
+
+        let mut hull = vec![mesh.origin(start)];
+        let mut border_edge = mesh.next_border_edge(start);
+        while border_edge != start {
+            hull.push(mesh.origin(border_edge));
+            border_edge = mesh.next_border_edge(border_edge);
+        }
+
+        hull
+    }
+
+    /// Boundary of the alpha shape of the points, a concave hull made of the triangles of a
+    /// finished triangulation whose circumcircle has a radius below `alpha`. A large enough
+    /// alpha gives the convex hull, and smaller ones carve ever deeper into it, until the shape
+    /// falls apart into pieces with holes in them. Each boundary is a loop of indices into the
+    /// point list with the shape on its left, so outer boundaries run counterclockwise and the
+    /// boundaries of holes clockwise. Points and edges outside every kept triangle are left
+    /// out.
+    #[must_use]
+    pub fn alpha_shape(&self, alpha: f64) -> Vec<Vec<usize>> {
+        let mesh = self.mesh();
+        let is_in_shape: Vec<bool> = mesh
+            .triangles()
+            .iter()
+            .map(|triangle| Circle::from(self.triangle_points(*triangle)).radius() < alpha)
+            .collect();
+        let is_on_boundary = |half_edge: HalfEdge| {
+            is_in_shape[half_edge.triangle]
+                && !mesh
+                    .twin(half_edge)
+                    .is_some_and(|twin| is_in_shape[twin.triangle])
+        };
+
+        let mut is_visited = vec![false; mesh.len() * 3];
+        let mut boundaries = vec![];
+        for start in (0..mesh.len())
+            .flat_map(|triangle_idx| (0..3).map(move |edge| HalfEdge::new(triangle_idx, edge)))
+        {
+            if !is_on_boundary(start) || is_visited[start.triangle * 3 + start.edge] {
+                continue;
+            }
+
+            let mut boundary = vec![];
+            let mut half_edge = start;
+            loop {
+                is_visited[half_edge.triangle * 3 + half_edge.edge] = true;
+                boundary.push(mesh.origin(half_edge));
+                // Rotating around the end of the edge through the shape's triangles leads to
+                // the next boundary edge, which keeps loops apart where they touch.
+                half_edge = half_edge.next();
+                while let Some(twin) = mesh
+                    .twin(half_edge)
+                    .filter(|twin| is_in_shape[twin.triangle])
+                {
+                    half_edge = twin.next();
+                }
+                if half_edge == start {
+                    break;
+                }
+            }
+            boundaries.push(boundary);
+        }
+
+        boundaries
+    }
+}
+
+#[cfg(feature = "gui")]
+impl DelauneyTriangulationInformation {
+    pub fn draw_convex_hull(&self, window: &mut RenderWindow) {
+        let hull = self.convex_hull();
+        let hull_outline: Vec<Vertex> = hull
+            .iter()
+            .chain(hull.first())
+            .map(|point_idx| {
+                Vertex::with_pos_color(self.vertex(*point_idx).into(), Color::rgb(255, 140, 0))
+            })
+            .collect();
+        window.draw_primitives(
+            &hull_outline,
+            PrimitiveType::LINE_STRIP,
+            &RenderStates::DEFAULT,
+        );
+    }
+
+    pub fn draw_alpha_shape(&self, window: &mut RenderWindow, alpha: f64) {
+        let boundary_lines: Vec<Vertex> = self
+            .alpha_shape(alpha)
+            .iter()
+            .flat_map(|boundary| {
+                (0..boundary.len()).flat_map(move |corner| {
+                    [boundary[corner], boundary[(corner + 1) % boundary.len()]]
+                })
+            })
+            .map(|point_idx| {
+                Vertex::with_pos_color(self.vertex(point_idx).into(), Color::rgb(50, 205, 50))
+            })
+            .collect();
+        window.draw_primitives(
+            &boundary_lines,
+            PrimitiveType::LINES,
+            &RenderStates::DEFAULT,
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{math::bounding_box, point::Point};
+
+/// Order the points of a point list get inserted into the triangulation in. Inserting points
+/// that lie close together one after another keeps the walk to each new point short.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PointOrdering {
+    /// The order of the point list, e.g. the order the points were clicked in.
+    #[default]
+    AsGiven,
+    /// Along a Hilbert curve laid over the bounding box of the points.
+    HilbertCurve,
+    /// Biased randomized insertion order: the points are split into rounds of doubling size by
+    /// random sampling, and each round is sorted along a Hilbert curve. This keeps the
+    /// locality of [`PointOrdering::HilbertCurve`] while avoiding its worst cases.
+    BiasedRandomized,
+}
+
+/// Side length of the grid points are snapped to before computing their Hilbert index.
+const HILBERT_GRID_SIZE: u32 = 1 << 16;
+/// Seed of the random rounds of [`PointOrdering::BiasedRandomized`], fixed so a point list is
+/// always inserted in the same order.
+const BRIO_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Reorders `point_indices`, which index into `point_list`, according to `ordering`.
+pub fn sort_point_indices(
+    point_list: &[Point],
+    point_indices: &mut [usize],
+    ordering: PointOrdering,
+) {
+    match ordering {
+        PointOrdering::AsGiven => {}
+        PointOrdering::HilbertCurve => {
+            let hilbert_indices = hilbert_indices(point_list);
+            point_indices.sort_by_key(|point_idx| hilbert_indices[*point_idx]);
+        }
+        PointOrdering::BiasedRandomized => {
+            let hilbert_indices = hilbert_indices(point_list);
+            let mut random_state = BRIO_SEED;
+            let rounds: Vec<u32> = point_list
+                .iter()
+                .map(|_| xorshift(&mut random_state).trailing_ones())
+                .collect();
+            // Every extra trailing one halves the chance of a point, so sorting by descending
+            // count puts the small rounds first and the last round holds about half the points.
+            point_indices.sort_by_key(|point_idx| {
+                (
+                    std::cmp::Reverse(rounds[*point_idx]),
+                    hilbert_indices[*point_idx],
+                )
+            });
+        }
+    }
+}
+
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+
+    *state
+}
+
+/// Position of every point along a Hilbert curve filling the bounding box of `point_list`.
+fn hilbert_indices(point_list: &[Point]) -> Vec<u64> {
+    let Some((min, max)) = bounding_box(point_list) else {
+        return vec![];
+    };
+    let extent = (max.x - min.x).max(max.y - min.y);
+    let scale = if extent > 0. {
+        f64::from(HILBERT_GRID_SIZE - 1) / extent
+    } else {
+        0.
+    };
+
+    point_list
+        .iter()
+        .map(|point| {
+            let grid_position = (*point - min) * scale;
+            hilbert_index(grid_position.x as u32, grid_position.y as u32)
+        })
+        .collect()
+}
+
+/// Distance along the Hilbert curve of the grid cell at `(x, y)`.
+fn hilbert_index(mut x: u32, mut y: u32) -> u64 {
+    let mut index = 0;
+    let mut cell_size = HILBERT_GRID_SIZE / 2;
+    while cell_size > 0 {
+        let in_right_half = x & cell_size != 0;
+        let in_upper_half = y & cell_size != 0;
+        let quadrant = match (in_right_half, in_upper_half) {
+            (false, false) => 0,
+            (false, true) => 1,
+            (true, true) => 2,
+            (true, false) => 3,
+        };
+        index += u64::from(cell_size) * u64::from(cell_size) * quadrant;
+
+        // Rotate the quadrant so the curve inside it starts and ends where the parent expects.
+        if !in_upper_half {
+            if in_right_half {
+                x = HILBERT_GRID_SIZE - 1 - x;
+                y = HILBERT_GRID_SIZE - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        cell_size /= 2;
+    }
+
+    index
+}
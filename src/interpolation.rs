@@ -0,0 +1,62 @@
+use crate::{delauney_triangulation::DelauneyTriangulationInformation, point::Point};
+
+impl DelauneyTriangulationInformation {
+    /// Value at `point` of the surface the triangles of the mesh span over the points and their
+    /// [`Self::values`], which is the values at the corners of the triangle containing the point
+    /// weighted by its barycentric coordinates. `None` outside the mesh, or when the points have
+    /// no values.
+    #[must_use]
+    pub fn interpolate(&self, point: Point) -> Option<f64> {
+        let (triangle_idx, weights) = self.locate(point)?;
+        let triangle = self.mesh().triangles()[triangle_idx];
+
+        (0..3).try_fold(0., |value, corner| {
+            Some(value + weights[corner] * self.values().get(triangle[corner])?)
+        })
+    }
+
+    /// Value at `point` of the smooth surface through the points and their [`Self::values`] that
+    /// natural neighbour interpolation gives, which unlike [`Self::interpolate`] has no creases
+    /// along the edges of the mesh. The values of the natural neighbours of the point are
+    /// weighted by [`Self::natural_neighbour_weights`]. `None` outside the mesh and on its
+    /// border, or when the points have no values.
+    #[must_use]
+    pub fn natural_neighbour_interpolate(&self, point: Point) -> Option<f64> {
+        self.natural_neighbour_weights(point)?
+            .into_iter()
+            .try_fold(0., |value, (neighbour, weight)| {
+                Some(value + weight * self.values().get(neighbour)?)
+            })
+    }
+
+    /// Samples [`Self::interpolate`] on a grid of `columns` by `rows` cells covering the
+    /// rectangle between `min` and `max`, at the center of each cell. The result holds one row
+    /// after the other, starting at `min.y`, each going from `min.x` to `max.x`. Cells whose
+    /// center is outside the mesh have no value.
+    #[must_use]
+    pub fn rasterize(
+        &self,
+        min: Point,
+        max: Point,
+        columns: usize,
+        rows: usize,
+    ) -> Vec<Vec<Option<f64>>> {
+        let cell_size = Point::new(
+            (max.x - min.x) / columns as f64,
+            (max.y - min.y) / rows as f64,
+        );
+
+        (0..rows)
+            .map(|row| {
+                (0..columns)
+                    .map(|column| {
+                        self.interpolate(Point::new(
+                            min.x + (column as f64 + 0.5) * cell_size.x,
+                            min.y + (row as f64 + 0.5) * cell_size.y,
+                        ))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
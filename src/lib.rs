@@ -0,0 +1,23 @@
+pub mod circle;
+pub mod contours;
+pub mod delauney_triangulation;
+pub mod error;
+pub mod hull;
+pub mod insertion_order;
+pub mod interpolation;
+pub mod math;
+pub mod mesh;
+pub mod point;
+pub mod proximity_graphs;
+pub mod quality;
+#[cfg(feature = "gui")]
+pub mod utils;
+pub mod voronoi;
+
+pub use delauney_triangulation::{
+    triangulate, triangulate_polygon, DelauneyTriangulationInformation,
+};
+pub use error::TriangulationError;
+pub use insertion_order::PointOrdering;
+pub use mesh::TriangleMesh;
+pub use point::Point;
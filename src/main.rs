@@ -1,15 +1,16 @@
-use delauney_triangulation::DelauneyTriangulationInformation;
+use delauney_triangulation::{
+    math::euclidian_distance, quality::QualityMetric, utils, DelauneyTriangulationInformation,
+    Point,
+};
 use sfml::{
-    graphics::{Color, RcFont, RcText, RenderStates, RenderTarget, RenderWindow, View},
-    system::{Vector2, Vector2f},
+    graphics::{
+        Color, PrimitiveType, RcFont, RcText, RenderStates, RenderTarget, RenderWindow,
+        Transformable, Vertex, View,
+    },
+    system::Vector2f,
     window::{mouse::Button, Event, Key, Style},
 };
 
-pub mod circle;
-pub mod delauney_triangulation;
-pub mod math;
-pub mod utils;
-
 fn setup_window() -> RenderWindow {
     let mut window = RenderWindow::new(
         (1280, 720),
@@ -35,13 +36,17 @@ fn load_font() -> RcFont {
 fn display_text(window: &mut RenderWindow, all_text_on_window: &[RcText]) {
     let rs = RenderStates::default();
     for text in all_text_on_window {
-        window.draw_rc_text(&text, &rs);
+        window.draw_rc_text(text, &rs);
     }
 }
 
 const CHARACTER_SIZE: u32 = 24;
 fn setup_text(font: &RcFont) -> Vec<RcText> {
     const DIRECTIONS_TEXT: &str = "Click anywhere on screen to add vertices
+Click once the triangulation is done to add vertices to it
+Right click a vertex to remove it
+Drag a vertex to move it
+Shift drag from one vertex to another to force an edge between them
 
 <Space> to start delauney triangulation
 <Space> to pause animation (if started and running)
@@ -50,6 +55,15 @@ fn setup_text(font: &RcFont) -> Vec<RcText> {
 <f> to make animation faster
 <s> to make animation slower
 <c> to go frame by frame (if paused)
+<q> to refine the finished triangulation until no angle is below 20.7 degrees
+<l> to start/stop spreading the points out evenly, one Lloyd relaxation step per animation step
+<o> to outline the convex hull
+<a> to outline the alpha shape, <Up>/<Down> to grow/shrink its alpha
+<i> to draw contour lines of the height the vertices are given, from blue for low to red for high
+<m> to switch between colouring no metric, or each triangle quality metric in turn, from green for good to red for bad
+<p> to show/hide a report of the triangle quality
+<g> to switch between showing no proximity graph, the minimum spanning tree, the Gabriel graph and the relative neighbourhood graph
+<v> to switch between the triangulation, the Voronoi diagram over it, and the Voronoi diagram alone
 <r> to remove all vertices
 <h> to hide/show help text";
 
@@ -58,11 +72,107 @@ fn setup_text(font: &RcFont) -> Vec<RcText> {
     vec![directions]
 }
 
+/// How far from a vertex, in pixels, a click still picks it.
+const VERTEX_PICK_RADIUS: f64 = 10.;
+fn vertex_under_mouse(vertices: &[Point], mouse_position: Point) -> Option<usize> {
+    vertices
+        .iter()
+        .enumerate()
+        .map(|(vertex_idx, vertex)| (vertex_idx, euclidian_distance(*vertex, mouse_position)))
+        .filter(|(_, distance)| *distance <= VERTEX_PICK_RADIUS)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(vertex_idx, _)| vertex_idx)
+}
+
+/// Once no point moves farther than this, in pixels, the relaxation started by <l> stops.
+const LLOYD_RELAXATION_TOLERANCE: f64 = 0.1;
+
+/// Circumradius, in pixels, below which triangles are part of the alpha shape at first.
+const INITIAL_ALPHA: f64 = 50.;
+/// Factor <Up> and <Down> grow and shrink the alpha of the alpha shape by.
+const ALPHA_STEP: f64 = 1.2;
+
+/// What the window shows of a finished triangulation, switched through with <v>.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum DiagramView {
+    #[default]
+    Triangulation,
+    TriangulationAndVoronoi,
+    Voronoi,
+}
+
+impl DiagramView {
+    fn next(self) -> Self {
+        match self {
+            DiagramView::Triangulation => DiagramView::TriangulationAndVoronoi,
+            DiagramView::TriangulationAndVoronoi => DiagramView::Voronoi,
+            DiagramView::Voronoi => DiagramView::Triangulation,
+        }
+    }
+}
+
+/// Height the viewer gives each vertex, a few hills and valleys across the window, so there
+/// are contour lines to draw.
+fn terrain_height(point: Point) -> f64 {
+    (point.x / 150.).sin() + (point.y / 110.).cos()
+}
+
+/// How many contour lines <i> draws, spread evenly over the heights of the vertices.
+const CONTOUR_LEVEL_COUNT: usize = 9;
+fn contour_levels(heights: &[f64]) -> Vec<f64> {
+    let lowest = heights.iter().copied().fold(f64::INFINITY, f64::min);
+    let highest = heights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    (0..CONTOUR_LEVEL_COUNT)
+        .map(|level| {
+            lowest + (highest - lowest) * (level as f64 + 0.5) / CONTOUR_LEVEL_COUNT as f64
+        })
+        .collect()
+}
+
+/// How many bins the histograms of the quality report shown by <p> have.
+const QUALITY_REPORT_BIN_COUNT: usize = 10;
+/// The quality report is long, so it gets smaller letters than the help text.
+const QUALITY_TEXT_CHARACTER_SIZE: u32 = 12;
+/// Gap, in pixels, between the quality text and the right edge of the window.
+const QUALITY_TEXT_MARGIN: f32 = 10.;
+/// Metric the heatmap switches to after `metric` when pressing <m>, going through all of them
+/// and then back to no heatmap.
+fn next_heatmap_metric(metric: Option<QualityMetric>) -> Option<QualityMetric> {
+    let next_idx = metric
+        .and_then(|metric| QualityMetric::ALL.iter().position(|other| *other == metric))
+        .map_or(0, |idx| idx + 1);
+    QualityMetric::ALL.get(next_idx).copied()
+}
+
+/// Which proximity graph of a finished triangulation the window shows, switched through with <g>.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ProximityGraph {
+    #[default]
+    Hidden,
+    MinimumSpanningTree,
+    Gabriel,
+    RelativeNeighbourhood,
+}
+
+impl ProximityGraph {
+    fn next(self) -> Self {
+        match self {
+            ProximityGraph::Hidden => ProximityGraph::MinimumSpanningTree,
+            ProximityGraph::MinimumSpanningTree => ProximityGraph::Gabriel,
+            ProximityGraph::Gabriel => ProximityGraph::RelativeNeighbourhood,
+            ProximityGraph::RelativeNeighbourhood => ProximityGraph::Hidden,
+        }
+    }
+}
+
+/// Smallest angle, in degrees, the refinement started by <q> leaves in the triangulation.
+const REFINEMENT_MINIMUM_ANGLE: f64 = 20.7;
 const FRAME_DURATION_INCREMENT_DECREMENT_AMOUNT: u8 = 2;
 fn main() {
     let mut window = setup_window();
     let font = load_font();
     let mut all_text_on_window = setup_text(&font);
+    let mut quality_text = RcText::new("", &font, QUALITY_TEXT_CHARACTER_SIZE);
     let mut vertices = vec![];
     let (mut is_animating, mut is_paused) = (false, true);
     let (mut num_of_frames_since_last_calculation, mut frame_duration_between_calculations): (
@@ -71,6 +181,18 @@ fn main() {
     ) = (0, 4);
     let mut delauney_triangulation_information = DelauneyTriangulationInformation::default();
     let mut hide_help_text = false;
+    let mut diagram_view = DiagramView::default();
+    let mut is_relaxing = false;
+    let mut show_convex_hull = false;
+    let mut show_alpha_shape = false;
+    let mut alpha = INITIAL_ALPHA;
+    let mut proximity_graph = ProximityGraph::default();
+    let mut show_contours = false;
+    let mut heatmap_metric = None;
+    let mut show_quality_report = false;
+    let mut dragged_vertex = None;
+    let mut constraint_start = None;
+    let mut mouse_position = Point::default();
 
     while window.is_open() {
         while let Some(event) = window.poll_event() {
@@ -84,11 +206,21 @@ fn main() {
                     for text in &mut all_text_on_window {
                         text.set_character_size((width / 1280. * CHARACTER_SIZE as f32) as u32);
                     }
+                    quality_text.set_character_size(
+                        (width / 1280. * QUALITY_TEXT_CHARACTER_SIZE as f32) as u32,
+                    );
                 }
                 Event::KeyPressed { code, .. } => match code {
                     Key::Space => {
                         if !is_animating {
-                            delauney_triangulation_information.set_point_list(vertices.clone());
+                            delauney_triangulation_information
+                                .set_point_list(
+                                    vertices.clone(),
+                                    Some(vertices.iter().copied().map(terrain_height).collect()),
+                                )
+                                .unwrap_or_else(|error| {
+                                    panic!("Clicked vertices are always finite: {error}")
+                                });
                         }
 
                         is_animating = true;
@@ -98,6 +230,7 @@ fn main() {
                         is_animating = false;
                         is_paused = true;
                         num_of_frames_since_last_calculation = 0;
+                        is_relaxing = false;
                         delauney_triangulation_information.reset_delauney_mesh();
                     }
                     Key::F => {
@@ -119,14 +252,130 @@ fn main() {
                     Key::H => {
                         hide_help_text = !hide_help_text;
                     }
+                    Key::L => {
+                        is_relaxing = !is_relaxing;
+                    }
+                    Key::O => {
+                        show_convex_hull = !show_convex_hull;
+                    }
+                    Key::A => {
+                        show_alpha_shape = !show_alpha_shape;
+                    }
+                    Key::Up => {
+                        alpha *= ALPHA_STEP;
+                    }
+                    Key::Down => {
+                        alpha /= ALPHA_STEP;
+                    }
+                    Key::I => {
+                        show_contours = !show_contours;
+                    }
+                    Key::M => {
+                        heatmap_metric = next_heatmap_metric(heatmap_metric);
+                    }
+                    Key::P => {
+                        show_quality_report = !show_quality_report;
+                    }
+                    Key::G => {
+                        proximity_graph = proximity_graph.next();
+                    }
+                    Key::V => {
+                        diagram_view = diagram_view.next();
+                    }
+                    Key::Q if delauney_triangulation_information.is_finished() => {
+                        delauney_triangulation_information
+                            .start_refinement(REFINEMENT_MINIMUM_ANGLE, None);
+                    }
                     Key::R => {
                         vertices = vec![];
+                        is_relaxing = false;
+                        dragged_vertex = None;
+                        constraint_start = None;
                     }
                     _ => {}
                 },
-                Event::MouseButtonPressed { button, x, y } => {
-                    if button == Button::Left && !is_animating {
-                        vertices.push(Vector2::new(x as f64, y as f64));
+                Event::MouseButtonPressed {
+                    button: Button::Left,
+                    x,
+                    y,
+                } => {
+                    let point = Point::new(x as f64, y as f64);
+                    let can_edit =
+                        !is_animating || delauney_triangulation_information.is_finished();
+                    let is_shift_held = Key::LShift.is_pressed() || Key::RShift.is_pressed();
+                    if let Some(vertex_idx) = vertex_under_mouse(&vertices, point) {
+                        if is_shift_held && delauney_triangulation_information.is_finished() {
+                            constraint_start = Some(vertex_idx);
+                        } else if can_edit {
+                            dragged_vertex = Some(vertex_idx);
+                        }
+                    } else if !is_animating {
+                        vertices.push(point);
+                    } else if delauney_triangulation_information.is_finished() {
+                        delauney_triangulation_information
+                            .insert_point(point)
+                            .unwrap_or_else(|error| {
+                                panic!("Clicked vertices are always finite: {error}")
+                            });
+                        vertices.push(point);
+                    }
+                }
+                Event::MouseButtonPressed {
+                    button: Button::Right,
+                    x,
+                    y,
+                } => {
+                    let Some(vertex_idx) =
+                        vertex_under_mouse(&vertices, Point::new(x as f64, y as f64))
+                    else {
+                        continue;
+                    };
+                    dragged_vertex = None;
+                    constraint_start = None;
+                    if !is_animating {
+                        vertices.swap_remove(vertex_idx);
+                    } else if delauney_triangulation_information.is_finished() {
+                        // The point list mirrors `vertices`, and both move the last point into
+                        // the freed slot.
+                        delauney_triangulation_information.remove_point(vertex_idx);
+                        vertices.swap_remove(vertex_idx);
+                    }
+                }
+                Event::MouseMoved { x, y } => {
+                    let point = Point::new(x as f64, y as f64);
+                    mouse_position = point;
+                    let Some(vertex_idx) = dragged_vertex else {
+                        continue;
+                    };
+                    vertices[vertex_idx] = point;
+                    if is_animating && delauney_triangulation_information.is_finished() {
+                        let dropped_constraints = delauney_triangulation_information
+                            .move_point(vertex_idx, point)
+                            .unwrap_or_else(|error| {
+                                panic!("Dragged vertices are always finite: {error}")
+                            });
+                        for (a, b) in dropped_constraints {
+                            eprintln!("Dropped the constraint between {a} and {b}, which crossed another one");
+                        }
+                    }
+                }
+                Event::MouseButtonReleased {
+                    button: Button::Left,
+                    x,
+                    y,
+                } => {
+                    dragged_vertex = None;
+                    let Some(start_idx) = constraint_start.take() else {
+                        continue;
+                    };
+                    if let Some(end_idx) =
+                        vertex_under_mouse(&vertices, Point::new(x as f64, y as f64))
+                    {
+                        if let Err(error) =
+                            delauney_triangulation_information.add_constraint(start_idx, end_idx)
+                        {
+                            eprintln!("Could not add the constraint: {error}");
+                        }
                     }
                 }
                 _ => {}
@@ -134,13 +383,89 @@ fn main() {
         }
 
         if num_of_frames_since_last_calculation >= frame_duration_between_calculations {
-            delauney_triangulation_information.update_triangulation();
+            if is_relaxing && delauney_triangulation_information.is_finished() {
+                let size = window.size();
+                let (farthest_move, dropped_constraints) = delauney_triangulation_information
+                    .lloyd_relaxation_step(
+                        Point::default(),
+                        Point::new(size.x as f64, size.y as f64),
+                    );
+                for (a, b) in dropped_constraints {
+                    eprintln!(
+                        "Dropped the constraint between {a} and {b}, which crossed another one"
+                    );
+                }
+                is_relaxing = farthest_move > LLOYD_RELAXATION_TOLERANCE;
+            } else {
+                delauney_triangulation_information.update_triangulation();
+            }
             num_of_frames_since_last_calculation = 0;
+            // Refining and relaxing add and move points on their own, which the vertices follow.
+            if is_animating {
+                vertices = delauney_triangulation_information.point_list().to_vec();
+            }
         }
 
         window.clear(Color::rgb(10, 10, 10));
         if is_animating {
-            delauney_triangulation_information.draw(&mut window);
+            if let Some(metric) =
+                heatmap_metric.filter(|_| delauney_triangulation_information.is_finished())
+            {
+                delauney_triangulation_information.draw_quality_heatmap(&mut window, metric);
+            }
+            let shows_voronoi = diagram_view != DiagramView::Triangulation
+                && delauney_triangulation_information.is_finished();
+            if shows_voronoi {
+                let size = window.size();
+                delauney_triangulation_information.draw_voronoi(
+                    &mut window,
+                    Point::default(),
+                    Point::new(size.x as f64, size.y as f64),
+                );
+            }
+            if shows_voronoi && diagram_view == DiagramView::Voronoi {
+                utils::display_vertices(&mut window, &vertices, Color::YELLOW);
+            } else {
+                delauney_triangulation_information.draw(&mut window);
+            }
+            if delauney_triangulation_information.is_finished() {
+                delauney_triangulation_information.draw_location(&mut window, mouse_position);
+            }
+            if show_contours && delauney_triangulation_information.is_finished() {
+                let levels = contour_levels(delauney_triangulation_information.values());
+                delauney_triangulation_information.draw_contours(&mut window, &levels);
+            }
+            if show_convex_hull && delauney_triangulation_information.is_finished() {
+                delauney_triangulation_information.draw_convex_hull(&mut window);
+            }
+            if show_alpha_shape && delauney_triangulation_information.is_finished() {
+                delauney_triangulation_information.draw_alpha_shape(&mut window, alpha);
+            }
+            if delauney_triangulation_information.is_finished() {
+                let (edges, color) = match proximity_graph {
+                    ProximityGraph::Hidden => (vec![], Color::TRANSPARENT),
+                    ProximityGraph::MinimumSpanningTree => (
+                        delauney_triangulation_information.euclidian_minimum_spanning_tree(),
+                        Color::RED,
+                    ),
+                    ProximityGraph::Gabriel => (
+                        delauney_triangulation_information.gabriel_graph(),
+                        Color::rgb(255, 105, 180),
+                    ),
+                    ProximityGraph::RelativeNeighbourhood => (
+                        delauney_triangulation_information.relative_neighbourhood_graph(),
+                        Color::rgb(30, 144, 255),
+                    ),
+                };
+                delauney_triangulation_information.draw_edges(&mut window, &edges, color);
+            }
+            if let Some(start_idx) = constraint_start {
+                let line = [
+                    Vertex::with_pos_color(vertices[start_idx].into(), Color::MAGENTA),
+                    Vertex::with_pos_color(mouse_position.into(), Color::MAGENTA),
+                ];
+                window.draw_primitives(&line, PrimitiveType::LINES, &RenderStates::DEFAULT);
+            }
         } else {
             utils::display_vertices(&mut window, &vertices, Color::YELLOW);
         }
@@ -148,6 +473,25 @@ fn main() {
         if !hide_help_text {
             display_text(&mut window, &all_text_on_window);
         }
+        if is_animating && delauney_triangulation_information.is_finished() {
+            let mut quality_lines = String::new();
+            if let Some(metric) = heatmap_metric {
+                quality_lines += &format!("Colouring triangles by {metric}\n\n");
+            }
+            if let Some(report) = delauney_triangulation_information
+                .quality_report(QUALITY_REPORT_BIN_COUNT)
+                .filter(|_| show_quality_report)
+            {
+                quality_lines += &report.to_string();
+            }
+            quality_text.set_string(quality_lines.as_str());
+            let text_width = quality_text.global_bounds().width;
+            quality_text.set_position(Vector2f::new(
+                window.size().x as f32 - text_width - QUALITY_TEXT_MARGIN,
+                QUALITY_TEXT_MARGIN,
+            ));
+            window.draw_rc_text(&quality_text, &RenderStates::default());
+        }
         window.display();
 
         if !is_paused && is_animating {
@@ -1,6 +1,5 @@
-use sfml::system::Vector2;
+use crate::point::Point;
 
-#[allow(non_snake_case)]
 #[must_use]
 pub fn cosine_rule_solved_for_angle_a(a: f64, b: f64, c: f64) -> f64 {
     // a^2 = b^2 + c^2 - 2ab * cos(A) is the law of cosines
@@ -9,23 +8,21 @@ pub fn cosine_rule_solved_for_angle_a(a: f64, b: f64, c: f64) -> f64 {
     let numerator = b * b + c * c - a * a;
     let denominator = 2. * b * c;
     let division_result = numerator / denominator;
-    let A = division_result.acos();
 
-    A
+    division_result.acos()
 }
 
 #[must_use]
-pub fn euclidian_distance(a: Vector2<f64>, b: Vector2<f64>) -> f64 {
+pub fn euclidian_distance(a: Point, b: Point) -> f64 {
     let diff = a - b;
     let squared_distance = diff.x * diff.x + diff.y * diff.y;
-    let euclidian_distance = squared_distance.sqrt();
 
-    euclidian_distance
+    squared_distance.sqrt()
 }
 
 #[allow(non_snake_case)]
 #[must_use]
-pub fn calculate_angles_of_triangle(triangle: [Vector2<f64>; 3]) -> (f64, f64, f64) {
+pub fn calculate_angles_of_triangle(triangle: [Point; 3]) -> (f64, f64, f64) {
     // Vertices of triangle
     let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
     // length of each side
@@ -45,30 +42,25 @@ pub fn calculate_angles_of_triangle(triangle: [Vector2<f64>; 3]) -> (f64, f64, f
     (A, B, C)
 }
 
-#[allow(non_snake_case)]
 #[must_use]
-pub fn circumcenter_of_triangle(triangle: [Vector2<f64>; 3]) -> Vector2<f64> {
-    // x = (x1 * sin(2A) + x2 * sin(2B) + x3 * sin(2C)) / (sin(2A) + sin(2B) + sin(2C))
-    // y = (y1 * sin(2A) + y2 * sin(2B) + y3 * sin(2C)) / (sin(2A) + sin(2B) + sin(2C))
-    let (a, b, c) = (triangle[0], triangle[1], triangle[2]);
-    let (A, B, C) = calculate_angles_of_triangle(triangle);
-
-    // sin(2*angle) of each angle
-    let (dA, dB, dC) = ((2. * A).sin(), (2. * B).sin(), (2. * C).sin());
+pub fn circumcenter_of_triangle(triangle: [Point; 3]) -> Point {
+    // Working relative to the first vertex keeps the products small, which avoids the
+    // cancellation the angle based formula ran into on thin triangles.
+    // ux = (cy * |b|^2 - by * |c|^2) / d, uy = (bx * |c|^2 - cx * |b|^2) / d, d = 2(bx * cy - by * cx)
+    let a = triangle[0];
+    let (b, c) = (triangle[1] - a, triangle[2] - a);
+    let (b_length_squared, c_length_squared) = (b.x * b.x + b.y * b.y, c.x * c.x + c.y * c.y);
+    let denominator = 2. * (b.x * c.y - b.y * c.x);
+    let offset = Point::new(
+        c.y * b_length_squared - b.y * c_length_squared,
+        b.x * c_length_squared - c.x * b_length_squared,
+    ) / denominator;
 
-    let denominator = dA + dB + dC;
-    let numerator = Vector2::new(
-        a.x * dA + b.x * dB + c.x * dC,
-        a.y * dA + b.y * dB + c.y * dC,
-    );
-
-    let circumcenter = numerator / denominator;
-
-    circumcenter
+    a + offset
 }
 
 #[must_use]
-pub fn get_edges_from_triangle(triangle: [Vector2<f64>; 3]) -> [(Vector2<f64>, Vector2<f64>); 3] {
+pub fn get_edges_from_triangle<T: Copy>(triangle: [T; 3]) -> [(T, T); 3] {
     [
         (triangle[0], triangle[1]),
         (triangle[1], triangle[2]),
@@ -77,10 +69,636 @@ pub fn get_edges_from_triangle(triangle: [Vector2<f64>; 3]) -> [(Vector2<f64>, V
 }
 
 #[must_use]
-pub fn edges_are_equal(
-    edge1: (Vector2<f64>, Vector2<f64>),
-    edge2: (Vector2<f64>, Vector2<f64>),
-) -> bool {
+pub fn edges_are_equal<T: PartialEq + Copy>(edge1: (T, T), edge2: (T, T)) -> bool {
     let flipped_edge1 = (edge1.1, edge1.0);
     edge1 == edge2 || flipped_edge1 == edge2
 }
+
+/// Center of mass of the area of a polygon whose corners are in counterclockwise order, or
+/// `None` when it has no area.
+#[must_use]
+pub fn polygon_centroid(polygon: &[Point]) -> Option<Point> {
+    let origin = *polygon.first()?;
+    let area = polygon_area(polygon);
+    let weighted_sum = polygon_edges_from_first_corner(polygon).fold(
+        Point::default(),
+        |weighted_sum, (corner, next_corner)| {
+            weighted_sum + (corner + next_corner) * cross_product(corner, next_corner)
+        },
+    );
+
+    (area > 0.).then(|| origin + weighted_sum / (6. * area))
+}
+
+/// The z component of the cross product of `a` and `b`, which is twice the signed area of the
+/// triangle they span. Unlike [`orient2d`] it is plain floating point arithmetic, for when the
+/// magnitude matters more than an exact sign.
+#[must_use]
+pub fn cross_product(a: Point, b: Point) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+/// Weights of the corners of `triangle` that sum up to `point`, which add up to one and are all
+/// between zero and one exactly when the point is inside the triangle.
+#[must_use]
+pub fn barycentric_coordinates(triangle: [Point; 3], point: Point) -> [f64; 3] {
+    let [a, b, c] = triangle;
+    // Relative to `a` and divided by the size of the triangle, so all weights share one
+    // determinant and none of the cross products overflow or underflow.
+    let size = [b - a, c - a]
+        .iter()
+        .fold(0_f64, |size, side| size.max(side.x.abs()).max(side.y.abs()));
+    let (ab, ac, ap) = ((b - a) / size, (c - a) / size, (point - a) / size);
+    let twice_area = cross_product(ab, ac);
+    let (b_weight, c_weight) = (
+        cross_product(ap, ac) / twice_area,
+        cross_product(ab, ap) / twice_area,
+    );
+
+    [1. - b_weight - c_weight, b_weight, c_weight]
+}
+
+/// Area of a polygon, positive when its corners are in counterclockwise order and negative
+/// when they are clockwise.
+#[must_use]
+pub fn polygon_area(polygon: &[Point]) -> f64 {
+    polygon_edges_from_first_corner(polygon)
+        .map(|(corner, next_corner)| cross_product(corner, next_corner))
+        .sum::<f64>()
+        / 2.
+}
+
+/// Edges of `polygon` as pairs of consecutive corners, relative to its first corner.
+fn polygon_edges_from_first_corner(polygon: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    // Working relative to the first corner keeps the products small, like in
+    // `circumcenter_of_triangle`.
+    let origin = polygon.first().copied().unwrap_or_default();
+    (0..polygon.len()).map(move |corner_idx| {
+        (
+            polygon[corner_idx] - origin,
+            polygon[(corner_idx + 1) % polygon.len()] - origin,
+        )
+    })
+}
+
+/// Smallest and largest coordinates over `points`, or `None` when there are no points.
+#[must_use]
+pub fn bounding_box(points: &[Point]) -> Option<(Point, Point)> {
+    let first = *points.first()?;
+
+    Some(points.iter().fold((first, first), |(min, max), point| {
+        (
+            Point::new(min.x.min(point.x), min.y.min(point.y)),
+            Point::new(max.x.max(point.x), max.y.max(point.y)),
+        )
+    }))
+}
+
+/// Machine epsilon as used by Shewchuk's error bounds, i.e. half an ulp of 1.
+const EPSILON: f64 = f64::EPSILON / 2.;
+const ORIENT2D_ERROR_BOUND: f64 = (3. + 16. * EPSILON) * EPSILON;
+const INCIRCLE_ERROR_BOUND: f64 = (10. + 96. * EPSILON) * EPSILON;
+/// Smallest and largest coordinate magnitudes [`orient2d`] works on as they are. Beyond them its
+/// products could overflow or underflow, which would break the exact sign.
+const ORIENT2D_COORDINATE_RANGE: (f64, f64) = (1e-120, 1e150);
+/// Like [`ORIENT2D_COORDINATE_RANGE`], but narrower since [`incircle`] multiplies four
+/// differences.
+const INCIRCLE_COORDINATE_RANGE: (f64, f64) = (1e-57, 1e60);
+
+/// Positive when `a`, `b` and `c` are in counterclockwise order (with the y axis pointing up),
+/// negative when clockwise and zero when collinear. The sign is always exact: the plain floating
+/// point determinant is used when it is provably correct, and the determinant is recomputed with
+/// exact expansion arithmetic otherwise. Points with huge or tiny coordinates get scaled by a
+/// power of two for the computation, and the result gets scaled back, see
+/// [`unscaled_determinant`].
+#[must_use]
+pub fn orient2d(a: Point, b: Point, c: Point) -> f64 {
+    if let Some(([a, b, c], exponent)) =
+        rescaled_for_predicate([a, b, c], ORIENT2D_COORDINATE_RANGE)
+    {
+        return unscaled_determinant(orient2d(a, b, c), 2 * exponent);
+    }
+
+    let det_left = (a.x - c.x) * (b.y - c.y);
+    let det_right = (a.y - c.y) * (b.x - c.x);
+    let det = det_left - det_right;
+
+    let det_sum = if det_left > 0. {
+        if det_right <= 0. {
+            return det;
+        }
+        det_left + det_right
+    } else if det_left < 0. {
+        if det_right >= 0. {
+            return det;
+        }
+        -det_left - det_right
+    } else {
+        return det;
+    };
+
+    let error_bound = ORIENT2D_ERROR_BOUND * det_sum;
+    if det >= error_bound || -det >= error_bound {
+        return det;
+    }
+
+    orient2d_exact(a, b, c)
+}
+
+/// Positive when `d` lies inside the circle through `a`, `b` and `c`, negative when outside and
+/// zero when the four points are cocircular, assuming `a`, `b` and `c` are counterclockwise (the
+/// sign flips otherwise). Exact in the same way as [`orient2d`].
+#[must_use]
+pub fn incircle(a: Point, b: Point, c: Point, d: Point) -> f64 {
+    if let Some(([a, b, c, d], exponent)) =
+        rescaled_for_predicate([a, b, c, d], INCIRCLE_COORDINATE_RANGE)
+    {
+        return unscaled_determinant(incircle(a, b, c, d), 4 * exponent);
+    }
+
+    let (adx, ady) = (a.x - d.x, a.y - d.y);
+    let (bdx, bdy) = (b.x - d.x, b.y - d.y);
+    let (cdx, cdy) = (c.x - d.x, c.y - d.y);
+
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let a_lift = adx * adx + ady * ady;
+
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let b_lift = bdx * bdx + bdy * bdy;
+
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let c_lift = cdx * cdx + cdy * cdy;
+
+    let det = a_lift * (bdxcdy - cdxbdy) + b_lift * (cdxady - adxcdy) + c_lift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * a_lift
+        + (cdxady.abs() + adxcdy.abs()) * b_lift
+        + (adxbdy.abs() + bdxady.abs()) * c_lift;
+
+    let error_bound = INCIRCLE_ERROR_BOUND * permanent;
+    if det > error_bound || -det > error_bound {
+        return det;
+    }
+
+    incircle_exact(a, b, c, d)
+}
+
+/// Whether `point` lies strictly inside the circumcircle of `triangle`, whatever the triangle's
+/// winding. Degenerate (collinear) triangles have no inside.
+#[must_use]
+pub fn is_point_inside_circumcircle(triangle: [Point; 3], point: Point) -> bool {
+    let orientation = orient2d(triangle[0], triangle[1], triangle[2]);
+    let incircle = incircle(triangle[0], triangle[1], triangle[2], point);
+
+    (orientation > 0. && incircle > 0.) || (orientation < 0. && incircle < 0.)
+}
+
+/// `points` divided by the power of two that brings their largest coordinate close to 1,
+/// together with its exponent, or `None` when the magnitude of that coordinate is within
+/// `coordinate_range` already. Scaling by a power of two is exact, unless the coordinates span
+/// so many orders of magnitude that the smallest ones drop below the normal range of `f64`.
+fn rescaled_for_predicate<const N: usize>(
+    points: [Point; N],
+    (min_magnitude, max_magnitude): (f64, f64),
+) -> Option<([Point; N], i32)> {
+    let magnitude = points.iter().fold(0_f64, |magnitude, point| {
+        magnitude.max(point.x.abs()).max(point.y.abs())
+    });
+    if (min_magnitude..=max_magnitude).contains(&magnitude) || magnitude == 0. {
+        return None;
+    }
+    let exponent = magnitude.log2().floor() as i32;
+
+    Some((
+        points.map(|point| {
+            Point::new(
+                times_power_of_two(point.x, -exponent),
+                times_power_of_two(point.y, -exponent),
+            )
+        }),
+        exponent,
+    ))
+}
+
+/// A determinant of points scaled down by 2^`exponent` per coordinate power, brought back to
+/// the scale of the original points, so results stay comparable between calls. Values beyond
+/// the range of `f64` become infinite, or the smallest float of the same sign, which keeps the
+/// sign exact.
+fn unscaled_determinant(determinant: f64, exponent: i32) -> f64 {
+    let unscaled = times_power_of_two(determinant, exponent);
+    if unscaled == 0. && determinant != 0. {
+        return f64::from_bits(1).copysign(determinant);
+    }
+
+    unscaled
+}
+
+/// `value` times 2^`exponent`, in steps, since powers of two beyond about 2^±1000 are not
+/// representable themselves.
+fn times_power_of_two(value: f64, exponent: i32) -> f64 {
+    let mut result = value;
+    let mut remaining = exponent;
+    while remaining != 0 {
+        let step = remaining.clamp(-1000, 1000);
+        result *= 2_f64.powi(step);
+        remaining -= step;
+    }
+
+    result
+}
+
+fn orient2d_exact(a: Point, b: Point, c: Point) -> f64 {
+    let (acx, acy) = (
+        difference_expansion(a.x, c.x),
+        difference_expansion(a.y, c.y),
+    );
+    let (bcx, bcy) = (
+        difference_expansion(b.x, c.x),
+        difference_expansion(b.y, c.y),
+    );
+
+    let det = expansion_difference(
+        &expansion_product(&acx, &bcy),
+        &expansion_product(&acy, &bcx),
+    );
+
+    expansion_sign_value(&det)
+}
+
+fn incircle_exact(a: Point, b: Point, c: Point, d: Point) -> f64 {
+    let (adx, ady) = (
+        difference_expansion(a.x, d.x),
+        difference_expansion(a.y, d.y),
+    );
+    let (bdx, bdy) = (
+        difference_expansion(b.x, d.x),
+        difference_expansion(b.y, d.y),
+    );
+    let (cdx, cdy) = (
+        difference_expansion(c.x, d.x),
+        difference_expansion(c.y, d.y),
+    );
+
+    let lift = |dx: &[f64], dy: &[f64]| {
+        expansion_sum(&expansion_product(dx, dx), &expansion_product(dy, dy))
+    };
+    let cross = |ux: &[f64], uy: &[f64], vx: &[f64], vy: &[f64]| {
+        expansion_difference(&expansion_product(ux, vy), &expansion_product(vx, uy))
+    };
+
+    let a_term = expansion_product(&lift(&adx, &ady), &cross(&bdx, &bdy, &cdx, &cdy));
+    let b_term = expansion_product(&lift(&bdx, &bdy), &cross(&cdx, &cdy, &adx, &ady));
+    let c_term = expansion_product(&lift(&cdx, &cdy), &cross(&adx, &ady, &bdx, &bdy));
+    let det = expansion_sum(&expansion_sum(&a_term, &b_term), &c_term);
+
+    expansion_sign_value(&det)
+}
+
+// Exact arithmetic on floating point expansions: a value is stored as a sum of non-overlapping
+// f64 components ordered by increasing magnitude, with zero components removed.
+
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+
+    (sum, b - b_virtual)
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    let (b_roundoff, a_roundoff) = (b - b_virtual, a - a_virtual);
+
+    (sum, a_roundoff + b_roundoff)
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+
+    (product, a.mul_add(b, -product))
+}
+
+fn difference_expansion(a: f64, b: f64) -> Vec<f64> {
+    let (difference, roundoff) = two_sum(a, -b);
+
+    [roundoff, difference]
+        .into_iter()
+        .filter(|component| *component != 0.)
+        .collect()
+}
+
+fn grow_expansion(expansion: &[f64], value: f64) -> Vec<f64> {
+    let mut grown = Vec::with_capacity(expansion.len() + 1);
+    let mut carry = value;
+    for component in expansion {
+        let (sum, roundoff) = two_sum(carry, *component);
+        if roundoff != 0. {
+            grown.push(roundoff);
+        }
+        carry = sum;
+    }
+    if carry != 0. {
+        grown.push(carry);
+    }
+
+    grown
+}
+
+fn expansion_sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(e.to_vec(), |sum, component| {
+        grow_expansion(&sum, *component)
+    })
+}
+
+fn expansion_difference(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let negated_f: Vec<f64> = f.iter().map(|component| -component).collect();
+
+    expansion_sum(e, &negated_f)
+}
+
+fn scale_expansion(expansion: &[f64], scale: f64) -> Vec<f64> {
+    let mut scaled = Vec::with_capacity(expansion.len() * 2);
+    let Some((first, rest)) = expansion.split_first() else {
+        return scaled;
+    };
+
+    let (mut carry, roundoff) = two_product(*first, scale);
+    if roundoff != 0. {
+        scaled.push(roundoff);
+    }
+    for component in rest {
+        let (product, product_roundoff) = two_product(*component, scale);
+        let (sum, roundoff) = two_sum(carry, product_roundoff);
+        if roundoff != 0. {
+            scaled.push(roundoff);
+        }
+        let (new_carry, roundoff) = fast_two_sum(product, sum);
+        if roundoff != 0. {
+            scaled.push(roundoff);
+        }
+        carry = new_carry;
+    }
+    if carry != 0. {
+        scaled.push(carry);
+    }
+
+    scaled
+}
+
+fn expansion_product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(vec![], |product, component| {
+        expansion_sum(&product, &scale_expansion(e, *component))
+    })
+}
+
+/// The most significant component, which carries the sign of the whole expansion.
+fn expansion_sign_value(expansion: &[f64]) -> f64 {
+    expansion.last().copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small xorshift generator, so the inputs are the same on every run.
+    struct Random(u64);
+
+    impl Random {
+        fn integer(&mut self, below: i64) -> i64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % below as u64) as i64
+        }
+    }
+
+    /// -1, 0 or 1, unlike `f64::signum`, which gives 1 for zero.
+    fn sign(value: f64) -> i128 {
+        if value > 0. {
+            1
+        } else if value < 0. {
+            -1
+        } else {
+            0
+        }
+    }
+
+    /// `value` times 2^`exponent`, which has to come out as a whole number.
+    fn scaled_integer(value: f64, exponent: i32) -> i128 {
+        let scaled = value * 2_f64.powi(exponent);
+        assert_eq!(
+            scaled.fract(),
+            0.,
+            "{value} is no multiple of 2^-{exponent}"
+        );
+        scaled as i128
+    }
+
+    /// Sign of `orient2d` worked out with integers, for points whose coordinates are all
+    /// multiples of 2^-`exponent`.
+    fn orient2d_reference(a: Point, b: Point, c: Point, exponent: i32) -> i128 {
+        let [ax, ay, bx, by, cx, cy] =
+            [a.x, a.y, b.x, b.y, c.x, c.y].map(|coordinate| scaled_integer(coordinate, exponent));
+
+        ((ax - cx) * (by - cy) - (ay - cy) * (bx - cx)).signum()
+    }
+
+    fn orient2d_plain(a: Point, b: Point, c: Point) -> f64 {
+        (a.x - c.x) * (b.y - c.y) - (a.y - c.y) * (b.x - c.x)
+    }
+
+    fn incircle_plain(a: Point, b: Point, c: Point, d: Point) -> f64 {
+        let (adx, ady, bdx, bdy, cdx, cdy) = (
+            a.x - d.x,
+            a.y - d.y,
+            b.x - d.x,
+            b.y - d.y,
+            c.x - d.x,
+            c.y - d.y,
+        );
+
+        (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy)
+            + (bdx * bdx + bdy * bdy) * (cdx * ady - adx * cdy)
+            + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady)
+    }
+
+    /// Points with whole number coordinates on the circle of radius `radius` around the origin,
+    /// in counterclockwise order.
+    fn points_on_circle(radius: i64) -> Vec<Point> {
+        let mut first_quadrant = vec![];
+        for x in 0..radius {
+            let y_squared = radius * radius - x * x;
+            let y = (y_squared as f64).sqrt() as i64;
+            if y * y == y_squared {
+                first_quadrant.push((x, y));
+            }
+        }
+        first_quadrant.reverse();
+
+        [(1, 1), (-1, 1), (-1, -1), (1, -1)]
+            .into_iter()
+            .flat_map(|(x_sign, y_sign)| {
+                let quadrant = first_quadrant.iter().map(move |(x, y)| {
+                    // Each quadrant is the first one turned by a right angle.
+                    match (x_sign, y_sign) {
+                        (1, 1) => (*x, *y),
+                        (-1, 1) => (-*y, *x),
+                        (-1, -1) => (-*x, -*y),
+                        _ => (*y, -*x),
+                    }
+                });
+                quadrant.map(|(x, y)| Point::new(x as f64, y as f64))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn orient2d_is_exact_for_nearly_collinear_points() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+        let mut plain_mistakes = 0;
+        for _ in 0..1000 {
+            let a = Point::new(
+                random.integer(1 << 45) as f64,
+                random.integer(1 << 45) as f64,
+            );
+            let direction = Point::new(
+                (random.integer(1 << 20) - (1 << 19)) as f64,
+                (random.integer(1 << 20) - (1 << 19)) as f64,
+            );
+            let offset = Point::new(
+                (random.integer(3) - 1) as f64,
+                (random.integer(3) - 1) as f64,
+            );
+            let b = a + direction;
+            let c = a + direction * random.integer(1 << 24) as f64 + offset;
+
+            let expected = orient2d_reference(a, b, c, 0);
+            assert_eq!(sign(orient2d(a, b, c)), expected, "{a:?} {b:?} {c:?}");
+            if sign(orient2d_plain(a, b, c)) != expected {
+                plain_mistakes += 1;
+            }
+        }
+        // Otherwise these inputs would not tell the exact predicate from the plain formula.
+        assert!(plain_mistakes > 0);
+    }
+
+    #[test]
+    fn orient2d_is_exact_when_the_differences_round() {
+        // The differences between these coordinates need more bits than an f64 has.
+        let tiny = 2_f64.powi(-20);
+        let (a, b) = (
+            Point::new(-tiny, -tiny),
+            Point::new(2_f64.powi(40), 2_f64.powi(40)),
+        );
+        for (c, expected) in [
+            (Point::new(3., 3.), 0),
+            (Point::new(3., 3. + tiny), 1),
+            (Point::new(3. + tiny, 3.), -1),
+            (Point::new(-2. * tiny, -2. * tiny), 0),
+            (Point::new(1e6, 1e6 - tiny), -1),
+        ] {
+            assert_eq!(orient2d_reference(a, b, c, 20), expected);
+            assert_eq!(sign(orient2d(a, b, c)), expected, "{c:?}");
+        }
+    }
+
+    #[test]
+    fn incircle_is_zero_for_cocircular_points() {
+        // 5^8 has many ways of being written as a sum of two squares.
+        let circle = points_on_circle(390_625);
+        assert!(circle.len() > 20);
+        let center = Point::new(1e9, -3e8);
+        let mut plain_mistakes = 0;
+        for corner in 0..circle.len() {
+            let [a, b, c, d] =
+                [0, 3, 7, 12].map(|step| circle[(corner + step * 5) % circle.len()] + center);
+            assert_eq!(incircle(a, b, c, d), 0., "{a:?} {b:?} {c:?} {d:?}");
+            if incircle_plain(a, b, c, d) != 0. {
+                plain_mistakes += 1;
+            }
+        }
+        assert!(plain_mistakes > 0);
+    }
+
+    #[test]
+    fn incircle_is_exact_for_nearly_cocircular_points() {
+        let circle = points_on_circle(390_625);
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+        let epsilon = 2_f64.powi(-33);
+        let mut plain_mistakes = 0;
+        for _ in 0..1000 {
+            let corners: Vec<usize> = (0..4)
+                .map(|_| random.integer(circle.len() as i64) as usize)
+                .collect();
+            let (mut abc, d_corner) = (corners[..3].to_vec(), corners[3]);
+            abc.sort_unstable();
+            abc.dedup();
+            if abc.len() < 3 || abc.contains(&d_corner) {
+                continue;
+            }
+            let [a, b, c] = [abc[0], abc[1], abc[2]].map(|corner| circle[corner]);
+            let nudge = (random.integer(7) - 3, random.integer(7) - 3);
+            let d = circle[d_corner] + Point::new(nudge.0 as f64, nudge.1 as f64) * epsilon;
+
+            // The nudge moves d out of the circle when the square of its distance to the
+            // center grows, by 2 * epsilon * (d · nudge) + epsilon^2 * |nudge|^2.
+            let on_circle = circle[d_corner];
+            let growth = 2
+                * (1_i128 << 33)
+                * (on_circle.x as i128 * nudge.0 as i128 + on_circle.y as i128 * nudge.1 as i128)
+                + (nudge.0 as i128).pow(2)
+                + (nudge.1 as i128).pow(2);
+            assert_eq!(
+                sign(incircle(a, b, c, d)),
+                -growth.signum(),
+                "{a:?} {b:?} {c:?} {d:?}"
+            );
+            if sign(incircle_plain(a, b, c, d)) != -growth.signum() {
+                plain_mistakes += 1;
+            }
+        }
+        assert!(plain_mistakes > 0);
+    }
+
+    #[test]
+    fn predicates_keep_their_magnitude_for_extreme_coordinates() {
+        let corner = 2_f64.powi(520);
+        let side = 2_f64.powi(490);
+        let (a, b, c) = (
+            Point::new(corner, corner),
+            Point::new(corner + side, corner),
+            Point::new(corner, corner + side),
+        );
+        assert_eq!(orient2d(a, b, c), 2_f64.powi(980));
+        assert_eq!(orient2d(a, c, b), -(2_f64.powi(980)));
+
+        // Too small to represent, but the sign is still there.
+        let tiny = [a, b, c].map(|point| point * 2_f64.powi(-600) * 2_f64.powi(-600));
+        assert!(orient2d(tiny[0], tiny[1], tiny[2]) > 0.);
+
+        // Scaling by 2^100 needs no rescaling inside incircle, 2^200 does.
+        let [a, b, c, d] = [(0., 0.), (3., 0.), (0., 2.), (1., 1.)].map(Point::from);
+        let scaled = |exponent: i32| {
+            let scale = 2_f64.powi(exponent);
+            incircle(a * scale, b * scale, c * scale, d * scale)
+        };
+        assert_eq!(scaled(200), scaled(100) * 2_f64.powi(400));
+        assert!(scaled(-400) > 0.);
+    }
+
+    #[test]
+    fn barycentric_coordinates_add_up_to_one_for_extreme_coordinates() {
+        let triangle = [(0., 0.), (8., 0.), (0., 8.)].map(Point::from);
+        for exponent in [-600, 0, 600] {
+            let scale = 2_f64.powi(exponent);
+            assert_eq!(
+                barycentric_coordinates(
+                    triangle.map(|corner| corner * scale),
+                    Point::new(1., 2.) * scale
+                ),
+                [0.625, 0.125, 0.25],
+                "scaled by 2^{exponent}"
+            );
+        }
+    }
+}
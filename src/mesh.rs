@@ -0,0 +1,417 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+/// Multiplicative hash for the vertex and triangle indices the mesh is keyed by. The default
+/// SipHash is built to resist untrusted keys, which costs more than the whole rest of a point
+/// insertion here.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndexHasher(u64);
+
+impl Hasher for IndexHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_u64(u64::from(*byte));
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+pub type IndexHashMap<K, V> = HashMap<K, V, BuildHasherDefault<IndexHasher>>;
+pub type IndexHashSet<T> = HashSet<T, BuildHasherDefault<IndexHasher>>;
+
+/// Directed edge `edge` of triangle `triangle`, running from the triangle's `edge`th vertex to
+/// the vertex after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HalfEdge {
+    pub triangle: usize,
+    pub edge: usize,
+}
+
+impl HalfEdge {
+    #[must_use]
+    pub const fn new(triangle: usize, edge: usize) -> Self {
+        HalfEdge { triangle, edge }
+    }
+
+    /// The half-edge after this one inside the same triangle.
+    #[must_use]
+    pub const fn next(self) -> Self {
+        HalfEdge::new(self.triangle, (self.edge + 1) % 3)
+    }
+
+    /// The half-edge before this one inside the same triangle.
+    #[must_use]
+    pub const fn prev(self) -> Self {
+        HalfEdge::new(self.triangle, (self.edge + 2) % 3)
+    }
+}
+
+const NO_NEIGHBOUR: usize = usize::MAX;
+const NO_TRIANGLE: usize = usize::MAX;
+
+/// Triangle mesh storing, for each triangle, the indices of its three vertices in the point list
+/// it was built from.
+///
+/// All triangles are expected to share the same winding order, so two neighbouring triangles
+/// traverse their shared edge in opposite directions. Neighbours are linked as triangles get
+/// added: half-edges without a twin yet are kept in a map by vertex pair, so the triangle that
+/// later fills in the other side finds them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TriangleMesh {
+    triangles: Vec<[usize; 3]>,
+    /// `neighbours[t][e]` is the triangle across half-edge `e` of triangle `t`.
+    neighbours: Vec<[usize; 3]>,
+    /// Half-edges on the border of the mesh, by `(origin, destination)`.
+    open_half_edges: IndexHashMap<(usize, usize), HalfEdge>,
+    /// A triangle touching each vertex, used as the starting point of [`Self::vertex_star`].
+    /// This is only a hint: it is checked before use, and vertex indices too large to store
+    /// densely are not tracked at all.
+    vertex_triangles: Vec<usize>,
+    /// Edges that have to stay in the mesh, by their vertices in ascending order.
+    constrained_edges: IndexHashSet<(usize, usize)>,
+}
+
+/// Largest vertex index [`TriangleMesh`] keeps a starting triangle for.
+const MAX_TRACKED_VERTEX: usize = u32::MAX as usize;
+
+impl TriangleMesh {
+    #[must_use]
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Returns the index the triangle was stored at.
+    pub fn add_triangle(&mut self, triangle: [usize; 3]) -> usize {
+        let triangle_idx = self.triangles.len();
+        self.triangles.push(triangle);
+        self.neighbours.push([NO_NEIGHBOUR; 3]);
+
+        for edge in 0..3 {
+            let half_edge = HalfEdge::new(triangle_idx, edge);
+            let (origin, destination) = self.edge_vertices(half_edge);
+            match self.open_half_edges.remove(&(destination, origin)) {
+                Some(twin) => {
+                    self.neighbours[triangle_idx][edge] = twin.triangle;
+                    self.neighbours[twin.triangle][twin.edge] = triangle_idx;
+                }
+                None => {
+                    self.open_half_edges
+                        .insert((origin, destination), half_edge);
+                }
+            }
+            self.set_vertex_triangle(origin, triangle_idx);
+        }
+
+        triangle_idx
+    }
+
+    /// Removes the triangle at `triangle_idx` by moving the last triangle into its slot, so only
+    /// the index of the last triangle changes.
+    pub fn remove_triangle(&mut self, triangle_idx: usize) -> [usize; 3] {
+        for edge in 0..3 {
+            let half_edge = HalfEdge::new(triangle_idx, edge);
+            let edge_vertices = self.edge_vertices(half_edge);
+            match self.twin(half_edge) {
+                Some(twin) => {
+                    self.neighbours[twin.triangle][twin.edge] = NO_NEIGHBOUR;
+                    self.open_half_edges
+                        .insert((edge_vertices.1, edge_vertices.0), twin);
+                }
+                None => {
+                    self.open_half_edges.remove(&edge_vertices);
+                }
+            }
+        }
+
+        let removed_triangle = self.triangles.swap_remove(triangle_idx);
+        self.neighbours.swap_remove(triangle_idx);
+
+        // Repoint everything that referred to the triangle which moved into the freed slot.
+        if triangle_idx < self.triangles.len() {
+            let moved_from = self.triangles.len();
+            for edge in 0..3 {
+                let half_edge = HalfEdge::new(triangle_idx, edge);
+                let neighbour = self.neighbours[triangle_idx][edge];
+                if neighbour == NO_NEIGHBOUR {
+                    self.open_half_edges
+                        .insert(self.edge_vertices(half_edge), half_edge);
+                } else {
+                    for neighbour_edge in &mut self.neighbours[neighbour] {
+                        if *neighbour_edge == moved_from {
+                            *neighbour_edge = triangle_idx;
+                        }
+                    }
+                }
+                self.set_vertex_triangle(self.origin(half_edge), triangle_idx);
+            }
+        }
+
+        removed_triangle
+    }
+
+    /// Removes every triangle in `triangle_indices`, which must not contain duplicates.
+    pub fn remove_triangles(&mut self, triangle_indices: &[usize]) {
+        let mut triangle_indices = triangle_indices.to_vec();
+        triangle_indices.sort_unstable_by(|a, b| b.cmp(a));
+        for triangle_idx in triangle_indices {
+            self.remove_triangle(triangle_idx);
+        }
+    }
+
+    fn set_vertex_triangle(&mut self, vertex: usize, triangle_idx: usize) {
+        if vertex > MAX_TRACKED_VERTEX {
+            return;
+        }
+        if vertex >= self.vertex_triangles.len() {
+            self.vertex_triangles.resize(vertex + 1, NO_TRIANGLE);
+        }
+        self.vertex_triangles[vertex] = triangle_idx;
+    }
+
+    #[must_use]
+    pub fn origin(&self, half_edge: HalfEdge) -> usize {
+        self.triangles[half_edge.triangle][half_edge.edge]
+    }
+
+    #[must_use]
+    pub fn destination(&self, half_edge: HalfEdge) -> usize {
+        self.origin(half_edge.next())
+    }
+
+    /// `(origin, destination)` of the half-edge.
+    #[must_use]
+    pub fn edge_vertices(&self, half_edge: HalfEdge) -> (usize, usize) {
+        (self.origin(half_edge), self.destination(half_edge))
+    }
+
+    /// The half-edge running from `origin` to `destination`, if any triangle has it.
+    #[must_use]
+    pub fn half_edge(&self, origin: usize, destination: usize) -> Option<HalfEdge> {
+        self.open_half_edges
+            .get(&(origin, destination))
+            .copied()
+            .or_else(|| {
+                self.vertex_out_edges(origin)
+                    .into_iter()
+                    .find(|half_edge| self.destination(*half_edge) == destination)
+            })
+    }
+
+    /// The same edge seen from the neighbouring triangle. `None` on the border of the mesh.
+    #[must_use]
+    pub fn twin(&self, half_edge: HalfEdge) -> Option<HalfEdge> {
+        let neighbour = self.neighbours[half_edge.triangle][half_edge.edge];
+        if neighbour == NO_NEIGHBOUR {
+            return None;
+        }
+
+        // The twin starts where this half-edge ends.
+        let destination = self.destination(half_edge);
+        self.triangles[neighbour]
+            .iter()
+            .position(|vertex| *vertex == destination)
+            .map(|edge| HalfEdge::new(neighbour, edge))
+    }
+
+    /// The border half-edge that starts where the border half-edge `half_edge` ends, found by
+    /// rotating around their shared vertex.
+    #[must_use]
+    pub fn next_border_edge(&self, half_edge: HalfEdge) -> HalfEdge {
+        let mut current = half_edge.next();
+        while let Some(twin) = self.twin(current) {
+            current = twin.next();
+        }
+
+        current
+    }
+
+    /// The border half-edge that ends where the border half-edge `half_edge` starts.
+    #[must_use]
+    pub fn previous_border_edge(&self, half_edge: HalfEdge) -> HalfEdge {
+        let mut current = half_edge.prev();
+        while let Some(twin) = self.twin(current) {
+            current = twin.prev();
+        }
+
+        current
+    }
+
+    /// Marks the edge between `a` and `b` as one that has to stay in the mesh, or clears the mark.
+    pub fn set_constrained(&mut self, a: usize, b: usize, is_constrained: bool) {
+        if is_constrained {
+            self.constrained_edges.insert((a.min(b), a.max(b)));
+        } else {
+            self.constrained_edges.remove(&(a.min(b), a.max(b)));
+        }
+    }
+
+    /// Whether the edge between `a` and `b` has to stay in the mesh.
+    #[must_use]
+    pub fn is_constrained(&self, a: usize, b: usize) -> bool {
+        !self.constrained_edges.is_empty() && self.constrained_edges.contains(&(a.min(b), a.max(b)))
+    }
+
+    /// Every constrained edge of the mesh, as pairs of vertex indices in ascending order.
+    pub fn constrained_edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.constrained_edges.iter().copied()
+    }
+
+    /// Triangles sharing an edge with `triangle_idx`, where the `n`th entry lies across
+    /// half-edge `n`.
+    #[must_use]
+    pub fn triangle_neighbours(&self, triangle_idx: usize) -> [Option<usize>; 3] {
+        self.neighbours[triangle_idx]
+            .map(|neighbour| (neighbour != NO_NEIGHBOUR).then_some(neighbour))
+    }
+
+    /// Half-edges leaving `vertex`, one per triangle around it, ordered by rotating around the
+    /// vertex. On the border of the mesh the first entry is the one at the border.
+    #[must_use]
+    pub fn vertex_out_edges(&self, vertex: usize) -> Vec<HalfEdge> {
+        let start_triangle = self
+            .vertex_triangles
+            .get(vertex)
+            .copied()
+            .filter(|triangle_idx| {
+                self.triangles
+                    .get(*triangle_idx)
+                    .is_some_and(|triangle| triangle.contains(&vertex))
+            })
+            .or_else(|| {
+                self.triangles
+                    .iter()
+                    .position(|triangle| triangle.contains(&vertex))
+            });
+        let Some(start_triangle) = start_triangle else {
+            return vec![];
+        };
+        let start_edge = self.triangles[start_triangle]
+            .iter()
+            .position(|triangle_vertex| *triangle_vertex == vertex)
+            .unwrap_or_default();
+        let start = HalfEdge::new(start_triangle, start_edge);
+
+        // Walk backwards to the border first, so the star of a border vertex comes out in one
+        // ordered run.
+        let mut first = start;
+        while let Some(twin) = self.twin(first.prev()) {
+            first = twin;
+            if first == start {
+                break;
+            }
+        }
+
+        let mut out_edges = vec![first];
+        let mut current = first;
+        while let Some(twin) = self.twin(current) {
+            current = twin.next();
+            if current == first {
+                break;
+            }
+            out_edges.push(current);
+        }
+
+        out_edges
+    }
+
+    /// Changes the index of vertex `from` to `to` in every triangle around it. `to` must not be
+    /// used by the mesh already.
+    pub fn rename_vertex(&mut self, from: usize, to: usize) {
+        let out_edges = self.vertex_out_edges(from);
+        // Border half-edges touching the vertex are keyed by it, so they get keyed again.
+        let border_edges: Vec<HalfEdge> = out_edges
+            .iter()
+            .flat_map(|half_edge| [*half_edge, half_edge.prev()])
+            .filter(|half_edge| self.twin(*half_edge).is_none())
+            .collect();
+        for half_edge in &border_edges {
+            self.open_half_edges.remove(&self.edge_vertices(*half_edge));
+        }
+
+        for half_edge in &out_edges {
+            self.triangles[half_edge.triangle][half_edge.edge] = to;
+        }
+        for half_edge in border_edges {
+            self.open_half_edges
+                .insert(self.edge_vertices(half_edge), half_edge);
+        }
+        if let Some(half_edge) = out_edges.first() {
+            self.set_vertex_triangle(to, half_edge.triangle);
+        }
+
+        let renamed_constraints: Vec<(usize, usize)> = self
+            .constrained_edges
+            .iter()
+            .copied()
+            .filter(|(a, b)| *a == from || *b == from)
+            .collect();
+        for (a, b) in renamed_constraints {
+            self.set_constrained(a, b, false);
+            let other = if a == from { b } else { a };
+            self.set_constrained(other, to, true);
+        }
+    }
+
+    /// Triangles touching `vertex`, ordered by rotating around it.
+    #[must_use]
+    pub fn vertex_star(&self, vertex: usize) -> Vec<usize> {
+        self.vertex_out_edges(vertex)
+            .into_iter()
+            .map(|half_edge| half_edge.triangle)
+            .collect()
+    }
+
+    /// Vertices sharing an edge with `vertex`, ordered by rotating around it.
+    #[must_use]
+    pub fn vertex_neighbours(&self, vertex: usize) -> Vec<usize> {
+        let out_edges = self.vertex_out_edges(vertex);
+        let mut neighbours: Vec<usize> = out_edges
+            .iter()
+            .map(|half_edge| self.destination(*half_edge))
+            .collect();
+        // On the border, the vertex across the first triangle's incoming edge has no outgoing
+        // edge of its own.
+        if let Some(first) = out_edges
+            .first()
+            .filter(|first| self.twin(first.prev()).is_none())
+        {
+            neighbours.insert(0, self.origin(first.prev()));
+        }
+
+        neighbours
+    }
+
+    /// Every edge of the mesh exactly once, as pairs of vertex indices.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.triangles.len())
+            .flat_map(|triangle_idx| (0..3).map(move |edge| HalfEdge::new(triangle_idx, edge)))
+            .filter(|half_edge| {
+                let (origin, destination) = self.edge_vertices(*half_edge);
+                origin < destination || self.twin(*half_edge).is_none()
+            })
+            .map(|half_edge| self.edge_vertices(half_edge))
+    }
+}
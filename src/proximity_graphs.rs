@@ -0,0 +1,128 @@
+#[cfg(feature = "gui")]
+use sfml::graphics::{Color, PrimitiveType, RenderStates, RenderTarget, RenderWindow, Vertex};
+
+use crate::{
+    delauney_triangulation::DelauneyTriangulationInformation,
+    math::euclidian_distance,
+    mesh::{HalfEdge, IndexHashSet},
+};
+
+/// Proximity graphs of the point list, which are all subgraphs of the Delauney triangulation,
+/// so each one is found by filtering the edges of a finished mesh. Edges are pairs of indices
+/// into the point list, and merged duplicates are left out.
+impl DelauneyTriangulationInformation {
+    /// Euclidian minimum spanning tree of the points: the edges connecting all of them with the
+    /// least total length, found with Kruskal's algorithm over the edges of the mesh.
+    #[must_use]
+    pub fn euclidian_minimum_spanning_tree(&self) -> Vec<(usize, usize)> {
+        let mut edges = self.proximity_graph_candidates();
+        edges.sort_by(|a, b| self.edge_length(*a).total_cmp(&self.edge_length(*b)));
+
+        // Each point starts out as a tree of its own, and an edge joining two trees is taken.
+        let mut parents: Vec<usize> = (0..self.point_list().len()).collect();
+        edges.retain(|(a, b)| {
+            let (a_root, b_root) = (find_root(&mut parents, *a), find_root(&mut parents, *b));
+            parents[a_root] = b_root;
+            a_root != b_root
+        });
+
+        edges
+    }
+
+    /// Gabriel graph of the points: the edges whose diametral circle, the smallest circle through
+    /// both ends, has no other point inside. In a Delauney triangulation only the vertices
+    /// across the edge can be inside.
+    #[must_use]
+    pub fn gabriel_graph(&self) -> Vec<(usize, usize)> {
+        let mesh = self.mesh();
+        let mut edges = self.proximity_graph_candidates();
+        if !mesh.is_empty() {
+            edges.retain(|(a, b)| {
+                let half_edge = mesh.half_edge(*a, *b);
+                half_edge
+                    .into_iter()
+                    .chain(half_edge.and_then(|half_edge| mesh.twin(half_edge)))
+                    .all(|half_edge: HalfEdge| {
+                        let apex = self.vertex(mesh.origin(half_edge.prev()));
+                        let (a, b) = (self.vertex(*a) - apex, self.vertex(*b) - apex);
+                        a.x * b.x + a.y * b.y >= 0.
+                    })
+            });
+        }
+
+        edges
+    }
+
+    /// Relative neighbourhood graph of the points: the edges with no other point closer to both
+    /// of its ends than they are to each other. Such a point lies within the edge's length of
+    /// its first end, and the points within that distance are connected in a Delauney
+    /// triangulation, so the search spreads out from that end through the mesh.
+    #[must_use]
+    pub fn relative_neighbourhood_graph(&self) -> Vec<(usize, usize)> {
+        let mesh = self.mesh();
+        let mut edges = self.proximity_graph_candidates();
+        if !mesh.is_empty() {
+            edges.retain(|(a, b)| {
+                let length = self.edge_length((*a, *b));
+                let mut visited: IndexHashSet<usize> = [*a, *b].into_iter().collect();
+                let mut to_visit = vec![*a];
+                while let Some(point_idx) = to_visit.pop() {
+                    for neighbour in mesh.vertex_neighbours(point_idx) {
+                        if self.edge_length((*a, neighbour)) >= length || !visited.insert(neighbour)
+                        {
+                            continue;
+                        }
+                        if self.edge_length((*b, neighbour)) < length {
+                            return false;
+                        }
+                        to_visit.push(neighbour);
+                    }
+                }
+
+                true
+            });
+        }
+
+        edges
+    }
+
+    fn edge_length(&self, (a, b): (usize, usize)) -> f64 {
+        euclidian_distance(self.vertex(a), self.vertex(b))
+    }
+
+    /// Edges of the mesh, or when there are no triangles because all points lie on one line,
+    /// the edges between consecutive points along it, which all three graphs consist of then.
+    fn proximity_graph_candidates(&self) -> Vec<(usize, usize)> {
+        if !self.mesh().is_empty() {
+            return self.mesh().edges().collect();
+        }
+
+        self.points_sorted_along_line(self.insertion_order().iter().copied())
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect()
+    }
+}
+
+#[cfg(feature = "gui")]
+impl DelauneyTriangulationInformation {
+    /// Draws edges given as pairs of indices into the point list, like the proximity graphs.
+    pub fn draw_edges(&self, window: &mut RenderWindow, edges: &[(usize, usize)], color: Color) {
+        let edge_lines: Vec<Vertex> = edges
+            .iter()
+            .flat_map(|(a, b)| [*a, *b])
+            .map(|point_idx| Vertex::with_pos_color(self.vertex(point_idx).into(), color))
+            .collect();
+        window.draw_primitives(&edge_lines, PrimitiveType::LINES, &RenderStates::DEFAULT);
+    }
+}
+
+/// Root of the tree a point belongs to, flattening the path there along the way.
+fn find_root(parents: &mut [usize], mut point_idx: usize) -> usize {
+    while parents[point_idx] != point_idx {
+        parents[point_idx] = parents[parents[point_idx]];
+        point_idx = parents[point_idx];
+    }
+
+    point_idx
+}
@@ -0,0 +1,252 @@
+use std::fmt;
+
+#[cfg(feature = "gui")]
+use sfml::graphics::{Color, PrimitiveType, RenderStates, RenderTarget, RenderWindow, Vertex};
+
+use crate::{
+    circle::Circle,
+    delauney_triangulation::DelauneyTriangulationInformation,
+    math::{
+        calculate_angles_of_triangle, euclidian_distance, get_edges_from_triangle, polygon_area,
+    },
+    point::Point,
+};
+
+/// Longest bar of a histogram in a [`QualityReport`], in characters.
+const HISTOGRAM_BAR_LENGTH: usize = 40;
+
+/// Shape and size of a triangle, for judging how well suited a mesh is for simulations and
+/// interpolation, which suffer from thin and flat triangles.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TriangleQuality {
+    /// Smallest angle, in degrees, which is 60 for an equilateral triangle and only gets smaller.
+    pub minimum_angle: f64,
+    /// Largest angle, in degrees, which is 60 for an equilateral triangle and only gets larger.
+    pub maximum_angle: f64,
+    /// Longest edge over the shortest altitude, scaled so an equilateral triangle has 1. Thin
+    /// and flat triangles both have large ones.
+    pub aspect_ratio: f64,
+    /// Circumradius over the shortest edge, which is what refinement keeps down, and is
+    /// 1/√3 for an equilateral triangle.
+    pub radius_edge_ratio: f64,
+    pub area: f64,
+}
+
+impl From<[Point; 3]> for TriangleQuality {
+    fn from(triangle: [Point; 3]) -> Self {
+        let (angle_a, angle_b, angle_c) = calculate_angles_of_triangle(triangle);
+        let edge_lengths = get_edges_from_triangle(triangle).map(|(a, b)| euclidian_distance(a, b));
+        let shortest_edge = edge_lengths.into_iter().fold(f64::INFINITY, f64::min);
+        let longest_edge = edge_lengths.into_iter().fold(0., f64::max);
+        let area = polygon_area(&triangle).abs();
+
+        TriangleQuality {
+            minimum_angle: angle_a.min(angle_b).min(angle_c).to_degrees(),
+            maximum_angle: angle_a.max(angle_b).max(angle_c).to_degrees(),
+            aspect_ratio: 3_f64.sqrt() * longest_edge * longest_edge / (4. * area),
+            radius_edge_ratio: Circle::from(triangle).radius() / shortest_edge,
+            area,
+        }
+    }
+}
+
+impl TriangleQuality {
+    #[must_use]
+    pub fn metric(&self, metric: QualityMetric) -> f64 {
+        match metric {
+            QualityMetric::MinimumAngle => self.minimum_angle,
+            QualityMetric::MaximumAngle => self.maximum_angle,
+            QualityMetric::AspectRatio => self.aspect_ratio,
+            QualityMetric::RadiusEdgeRatio => self.radius_edge_ratio,
+            QualityMetric::Area => self.area,
+        }
+    }
+}
+
+/// One of the measures in a [`TriangleQuality`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityMetric {
+    MinimumAngle,
+    MaximumAngle,
+    AspectRatio,
+    RadiusEdgeRatio,
+    Area,
+}
+
+impl QualityMetric {
+    pub const ALL: [QualityMetric; 5] = [
+        QualityMetric::MinimumAngle,
+        QualityMetric::MaximumAngle,
+        QualityMetric::AspectRatio,
+        QualityMetric::RadiusEdgeRatio,
+        QualityMetric::Area,
+    ];
+
+    /// Whether larger values mean a worse shaped triangle. Area has no better or worse, and
+    /// counts as worse when larger.
+    #[must_use]
+    pub fn is_worse_when_larger(self) -> bool {
+        self != QualityMetric::MinimumAngle
+    }
+}
+
+impl fmt::Display for QualityMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QualityMetric::MinimumAngle => write!(f, "minimum angle (degrees)"),
+            QualityMetric::MaximumAngle => write!(f, "maximum angle (degrees)"),
+            QualityMetric::AspectRatio => write!(f, "aspect ratio"),
+            QualityMetric::RadiusEdgeRatio => write!(f, "circumradius to shortest edge ratio"),
+            QualityMetric::Area => write!(f, "area"),
+        }
+    }
+}
+
+/// How one metric is spread over the triangles of a mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSummary {
+    pub metric: QualityMetric,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub mean: f64,
+    /// How many triangles fall into each of a number of equally wide bins from `minimum` to
+    /// `maximum`.
+    pub histogram: Vec<usize>,
+}
+
+impl MetricSummary {
+    fn new(metric: QualityMetric, qualities: &[TriangleQuality], bin_count: usize) -> Self {
+        let values = qualities.iter().map(|quality| quality.metric(metric));
+        let minimum = values.clone().fold(f64::INFINITY, f64::min);
+        let maximum = values.clone().fold(f64::NEG_INFINITY, f64::max);
+        let mean = values.clone().sum::<f64>() / qualities.len() as f64;
+
+        let mut histogram = vec![0; bin_count.max(1)];
+        for value in values {
+            let along = if maximum > minimum {
+                (value - minimum) / (maximum - minimum)
+            } else {
+                0.
+            };
+            let bin = ((along * histogram.len() as f64) as usize).min(histogram.len() - 1);
+            histogram[bin] += 1;
+        }
+
+        MetricSummary {
+            metric,
+            minimum,
+            maximum,
+            mean,
+            histogram,
+        }
+    }
+}
+
+/// Summary of the quality of all triangles of a mesh, which prints as a table with a histogram
+/// per metric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityReport {
+    pub triangle_count: usize,
+    /// One summary for each of [`QualityMetric::ALL`], in that order.
+    pub summaries: Vec<MetricSummary>,
+}
+
+impl fmt::Display for QualityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Quality of {} triangles", self.triangle_count)?;
+        for summary in &self.summaries {
+            writeln!(
+                f,
+                "\n{}: {:.3} to {:.3}, mean {:.3}",
+                summary.metric, summary.minimum, summary.maximum, summary.mean
+            )?;
+            let largest_count = summary.histogram.iter().copied().max().unwrap_or_default();
+            let bin_width = (summary.maximum - summary.minimum) / summary.histogram.len() as f64;
+            for (bin, count) in summary.histogram.iter().enumerate() {
+                let bin_start = summary.minimum + bin as f64 * bin_width;
+                let bar_length = count * HISTOGRAM_BAR_LENGTH / largest_count.max(1);
+                writeln!(
+                    f,
+                    "{:>12.3} to {:>12.3} {:>8} {}",
+                    bin_start,
+                    bin_start + bin_width,
+                    count,
+                    "#".repeat(bar_length)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DelauneyTriangulationInformation {
+    /// Quality of each triangle of the mesh, in the order of [`crate::TriangleMesh::triangles`].
+    #[must_use]
+    pub fn triangle_qualities(&self) -> Vec<TriangleQuality> {
+        self.mesh()
+            .triangles()
+            .iter()
+            .map(|triangle| TriangleQuality::from(self.triangle_points(*triangle)))
+            .collect()
+    }
+
+    /// Summary of [`Self::triangle_qualities`] with histograms of `bin_count` bins, or `None`
+    /// when there are no triangles.
+    #[must_use]
+    pub fn quality_report(&self, bin_count: usize) -> Option<QualityReport> {
+        let qualities = self.triangle_qualities();
+        if qualities.is_empty() {
+            return None;
+        }
+
+        Some(QualityReport {
+            triangle_count: qualities.len(),
+            summaries: QualityMetric::ALL
+                .into_iter()
+                .map(|metric| MetricSummary::new(metric, &qualities, bin_count))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(feature = "gui")]
+impl DelauneyTriangulationInformation {
+    /// Fills the triangles with colours by `metric`, from green for the best value in the mesh
+    /// to red for the worst.
+    pub fn draw_quality_heatmap(&self, window: &mut RenderWindow, metric: QualityMetric) {
+        let values: Vec<f64> = self
+            .triangle_qualities()
+            .iter()
+            .map(|quality| quality.metric(metric))
+            .collect();
+        let minimum = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let maximum = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let heatmap: Vec<Vertex> = self
+            .mesh()
+            .triangles()
+            .iter()
+            .zip(values)
+            .flat_map(|(triangle, value)| {
+                let mut badness = if maximum > minimum {
+                    (value - minimum) / (maximum - minimum)
+                } else {
+                    0.
+                };
+                if !metric.is_worse_when_larger() {
+                    badness = 1. - badness;
+                }
+                let color = Color::rgba(
+                    (255. * badness) as u8,
+                    (255. * (1. - badness)) as u8,
+                    0,
+                    120,
+                );
+                self.triangle_points(*triangle)
+                    .map(|corner| Vertex::with_pos_color(corner.into(), color))
+            })
+            .collect();
+        window.draw_primitives(&heatmap, PrimitiveType::TRIANGLES, &RenderStates::DEFAULT);
+    }
+}
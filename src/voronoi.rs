@@ -0,0 +1,328 @@
+#[cfg(feature = "gui")]
+use sfml::graphics::{Color, PrimitiveType, RenderStates, RenderTarget, RenderWindow, Vertex};
+
+use crate::{
+    circle::Circle,
+    delauney_triangulation::DelauneyTriangulationInformation,
+    math::{cross_product, euclidian_distance, polygon_centroid},
+    point::Point,
+};
+
+/// How far out the points closing off an unbounded cell go, as a multiple of the distance from
+/// the center of the clipping rectangle to the farthest corner involved. This keeps the edges
+/// closing off the cell outside the rectangle.
+const UNBOUNDED_CELL_REACH: f64 = 4.;
+
+impl DelauneyTriangulationInformation {
+    /// Voronoi cell of each point of the point list, as the corners of a convex polygon in
+    /// counterclockwise order, clipped to the rectangle between `min` and `max`. The corners are
+    /// the circumcenters of the triangles around the point, and the cells of points on the
+    /// convex hull, which reach out to infinity, get cut off by the rectangle. A merged
+    /// duplicate shares the cell of the point it was merged into, and a cell outside the
+    /// rectangle is empty.
+    ///
+    /// This is meant for a finished triangulation. Constraints and removed triangles are not
+    /// taken into account, so the cells only form the Voronoi diagram of a plain Delauney
+    /// triangulation.
+    #[must_use]
+    pub fn voronoi_cells(&self, min: Point, max: Point) -> Vec<Vec<Point>> {
+        let rectangle = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+        let mut cells = if self.mesh().is_empty() {
+            self.voronoi_cells_along_line(&rectangle)
+        } else {
+            let mut cells = vec![vec![]; self.point_list().len()];
+            for point_idx in self.insertion_order() {
+                cells[*point_idx] = self.voronoi_cell(*point_idx, &rectangle);
+            }
+            cells
+        };
+        for (duplicate, kept) in self.merged_points() {
+            cells[*duplicate] = cells[*kept].clone();
+        }
+
+        cells
+    }
+
+    fn voronoi_cell(&self, point_idx: usize, rectangle: &[Point; 4]) -> Vec<Point> {
+        let mesh = self.mesh();
+        let out_edges = mesh.vertex_out_edges(point_idx);
+        let (Some(first), Some(last)) = (out_edges.first(), out_edges.last()) else {
+            return vec![];
+        };
+        // The triangles around the point come in clockwise order, so the cell gets built
+        // clockwise and turned around at the end.
+        let mut cell: Vec<Point> = out_edges
+            .iter()
+            .map(|half_edge| {
+                let triangle = mesh.triangles()[half_edge.triangle];
+                Circle::from(self.triangle_points(triangle)).center()
+            })
+            .collect();
+
+        if mesh.twin(first.prev()).is_none() {
+            // On the convex hull the cell opens up to the outside, between the outward normals
+            // of the two hull edges at the point. Points far out along them, and between them,
+            // close it off beyond the rectangle.
+            let point = self.vertex(point_idx);
+            let incoming_normal = outward_normal(self.vertex(mesh.origin(first.prev())), point);
+            let outgoing_normal = outward_normal(point, self.vertex(mesh.destination(*last)));
+            let middle_normal = incoming_normal + outgoing_normal;
+            let middle_normal = middle_normal / euclidian_distance(middle_normal, Point::default());
+
+            let center = (rectangle[0] + rectangle[2]) / 2.;
+            let reach = UNBOUNDED_CELL_REACH
+                * cell
+                    .iter()
+                    .chain([&point, &rectangle[0]])
+                    .map(|corner| euclidian_distance(*corner, center))
+                    .fold(0., f64::max);
+            let (first_corner, last_corner) = (cell[0], cell[cell.len() - 1]);
+            cell.insert(0, first_corner + incoming_normal * reach);
+            cell.push(last_corner + outgoing_normal * reach);
+            cell.push(point + middle_normal * reach);
+        }
+        cell.reverse();
+
+        for corner in 0..rectangle.len() {
+            cell = clip_polygon(&cell, rectangle[corner], rectangle[(corner + 1) % 4]);
+        }
+
+        cell
+    }
+
+    /// One iteration of Lloyd's relaxation: moves every point to the centroid of its Voronoi
+    /// cell, clipped to the rectangle between `min` and `max`, which spreads the points out
+    /// more evenly. The points keep their indices, and merged duplicates move along with the
+    /// point they were merged into. Without constraints the triangulation gets built again for
+    /// the moved points, which is faster than moving them one by one. With constraints each
+    /// point gets moved with [`Self::move_point`], which drops constraints that end up crossing
+    /// others. Returns the farthest any point moved, and the dropped constraints.
+    pub fn lloyd_relaxation_step(&mut self, min: Point, max: Point) -> (f64, Vec<(usize, usize)>) {
+        let cells = self.voronoi_cells(min, max);
+        let centroids: Vec<Option<Point>> =
+            cells.iter().map(|cell| polygon_centroid(cell)).collect();
+        let farthest_move = centroids
+            .iter()
+            .enumerate()
+            .filter_map(|(point_idx, centroid)| {
+                centroid.map(|centroid| euclidian_distance(self.vertex(point_idx), centroid))
+            })
+            .fold(0., f64::max);
+
+        if !self.constraints().is_empty() {
+            let mut dropped_constraints = vec![];
+            for point_idx in self.insertion_order().to_vec() {
+                let Some(centroid) = centroids[point_idx] else {
+                    continue;
+                };
+                dropped_constraints.extend(
+                    self.move_point(point_idx, centroid)
+                        .expect("the centroid of a cell with an area is finite"),
+                );
+            }
+            return (farthest_move, dropped_constraints);
+        }
+
+        let moved_points: Vec<Point> = self
+            .point_list()
+            .iter()
+            .zip(centroids)
+            .map(|(point, centroid)| centroid.unwrap_or(*point))
+            .collect();
+        let values = (!self.values().is_empty()).then(|| self.values().to_vec());
+        self.set_point_list(moved_points, values)
+            .expect("the centroid of a cell with an area is finite");
+        self.finish_triangulation();
+
+        (farthest_move, vec![])
+    }
+
+    /// Runs up to `iterations` of [`Self::lloyd_relaxation_step`], stopping early once no point
+    /// moves farther than `tolerance`. Returns how many iterations ran, and the constraints that
+    /// got dropped along the way.
+    pub fn lloyd_relaxation(
+        &mut self,
+        min: Point,
+        max: Point,
+        iterations: usize,
+        tolerance: f64,
+    ) -> (usize, Vec<(usize, usize)>) {
+        let mut dropped_constraints = vec![];
+        for iteration in 1..=iterations {
+            let (farthest_move, dropped_in_step) = self.lloyd_relaxation_step(min, max);
+            dropped_constraints.extend(dropped_in_step);
+            if farthest_move <= tolerance {
+                return (iteration, dropped_constraints);
+            }
+        }
+
+        (iterations, dropped_constraints)
+    }
+
+    /// Voronoi cells for when there are no triangles, because all points lie on one line. Each
+    /// cell is the strip of the rectangle between the bisectors with the points before and
+    /// after it along the line.
+    fn voronoi_cells_along_line(&self, rectangle: &[Point; 4]) -> Vec<Vec<Point>> {
+        let points_along_line =
+            self.points_sorted_along_line(self.insertion_order().iter().copied());
+
+        let mut cells = vec![vec![]; self.point_list().len()];
+        for (order, point_idx) in points_along_line.iter().enumerate() {
+            let point = self.vertex(*point_idx);
+            let mut cell = rectangle.to_vec();
+            for neighbour in [order.checked_sub(1), Some(order + 1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|order| points_along_line.get(order))
+            {
+                let offset = self.vertex(*neighbour) - point;
+                let middle = point + offset / 2.;
+                // The bisector, directed so the point is on its left.
+                cell = clip_polygon(&cell, middle, middle + Point::new(-offset.y, offset.x));
+            }
+            cells[*point_idx] = cell;
+        }
+
+        cells
+    }
+}
+
+#[cfg(feature = "gui")]
+impl DelauneyTriangulationInformation {
+    pub fn draw_voronoi(&self, window: &mut RenderWindow, min: Point, max: Point) {
+        let cell_lines: Vec<Vertex> = self
+            .voronoi_cells(min, max)
+            .iter()
+            .flat_map(|cell| {
+                (0..cell.len())
+                    .flat_map(move |corner| [cell[corner], cell[(corner + 1) % cell.len()]])
+            })
+            .map(|corner| Vertex::with_pos_color(corner.into(), Color::CYAN))
+            .collect();
+        window.draw_primitives(&cell_lines, PrimitiveType::LINES, &RenderStates::DEFAULT);
+    }
+}
+
+/// Unit normal of the edge from `a` to `b` pointing to its right, which is the outside for an
+/// edge of a counterclockwise polygon.
+fn outward_normal(a: Point, b: Point) -> Point {
+    let direction = b - a;
+    Point::new(direction.y, -direction.x) / euclidian_distance(a, b)
+}
+
+/// Cuts off the part of a convex polygon to the right of the line from `a` through `b`.
+fn clip_polygon(polygon: &[Point], a: Point, b: Point) -> Vec<Point> {
+    let mut clipped = vec![];
+    for (corner_idx, corner) in polygon.iter().enumerate() {
+        let next_corner = polygon[(corner_idx + 1) % polygon.len()];
+        let (side, next_side) = (
+            cross_product(b - a, *corner - a),
+            cross_product(b - a, next_corner - a),
+        );
+        if side >= 0. {
+            clipped.push(*corner);
+        }
+        if (side > 0. && next_side < 0.) || (side < 0. && next_side > 0.) {
+            let along = side / (side - next_side);
+            clipped.push(*corner + (next_corner - *corner) * along);
+        }
+    }
+
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{delauney_triangulation::triangulate, math::polygon_area};
+
+    /// Standard deviation of the areas of the cells over their mean.
+    fn cell_area_spread(triangulation: &DelauneyTriangulationInformation, max: Point) -> f64 {
+        let areas: Vec<f64> = triangulation
+            .voronoi_cells(Point::default(), max)
+            .iter()
+            .map(|cell| polygon_area(cell))
+            .collect();
+        let mean = areas.iter().sum::<f64>() / areas.len() as f64;
+        let variance =
+            areas.iter().map(|area| (area - mean).powi(2)).sum::<f64>() / areas.len() as f64;
+
+        variance.sqrt() / mean
+    }
+
+    /// Checks that the cells cover the rectangle between `min` and `max` without overlapping,
+    /// and that each point lies in its own cell.
+    fn assert_cells_tile_the_rectangle(
+        triangulation: &DelauneyTriangulationInformation,
+        min: Point,
+        max: Point,
+    ) {
+        let cells = triangulation.voronoi_cells(min, max);
+        let area: f64 = cells.iter().map(|cell| polygon_area(cell)).sum();
+        let rectangle_area = (max.x - min.x) * (max.y - min.y);
+        assert!(
+            (area - rectangle_area).abs() < 1e-9 * rectangle_area,
+            "{area} instead of {rectangle_area}"
+        );
+
+        for (point, cell) in triangulation.point_list().iter().zip(&cells) {
+            for (corner_idx, corner) in cell.iter().enumerate() {
+                let next_corner = cell[(corner_idx + 1) % cell.len()];
+                assert!(
+                    cross_product(next_corner - *corner, *point - *corner) >= 0.,
+                    "{point:?} is outside its cell"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn voronoi_cells_tile_the_rectangle() {
+        let points: Vec<Point> = (0..60)
+            .map(|point_idx| {
+                Point::new((point_idx * 37 % 101) as f64, (point_idx * 59 % 103) as f64)
+            })
+            .collect();
+        let triangulation = triangulate(points).unwrap();
+        assert_cells_tile_the_rectangle(
+            &triangulation,
+            Point::new(-10., -10.),
+            Point::new(120., 120.),
+        );
+
+        // Without triangles the cells are strips across the line.
+        let line: Vec<Point> = (0..10)
+            .map(|point_idx| Point::new(point_idx as f64 * 7., point_idx as f64 * 3.))
+            .collect();
+        let triangulation = triangulate(line).unwrap();
+        assert!(triangulation.mesh().is_empty());
+        assert_cells_tile_the_rectangle(
+            &triangulation,
+            Point::new(-10., -10.),
+            Point::new(120., 120.),
+        );
+    }
+
+    #[test]
+    fn lloyd_relaxation_evens_out_the_cell_areas() {
+        // Bunched up in one corner of the rectangle.
+        let points: Vec<Point> = (0..60)
+            .map(|point_idx| {
+                Point::new((point_idx * 37 % 101) as f64, (point_idx * 59 % 103) as f64)
+            })
+            .collect();
+        let max = Point::new(200., 200.);
+        let mut triangulation = triangulate(points).unwrap();
+        let spread_before = cell_area_spread(&triangulation, max);
+
+        let (iterations, dropped_constraints) =
+            triangulation.lloyd_relaxation(Point::default(), max, 50, 0.);
+        assert_eq!(iterations, 50);
+        assert!(dropped_constraints.is_empty());
+        let spread_after = cell_area_spread(&triangulation, max);
+        assert!(
+            spread_after < spread_before / 4.,
+            "{spread_before} to {spread_after}"
+        );
+    }
+}